@@ -1,10 +1,11 @@
-use rust_contract::msg::{ExecuteMsg, InstantiateMsg, QueryMsg};
+use rust_contract::msg::{ExecuteMsg, InstantiateMsg, MigrateMsg, QueryMsg};
 use cosmwasm_schema::write_api;
 
 fn main() {
     write_api! {
         instantiate: InstantiateMsg,
         execute: ExecuteMsg,
-        query: QueryMsg
+        query: QueryMsg,
+        migrate: MigrateMsg
     }
 }
\ No newline at end of file
@@ -1,25 +1,51 @@
 #[cfg(not(feature = "library"))]
 use crate::{
     msg::{
-        ExecuteMsg, GetPriceResponse, GetShareBalanceResponse, GetSubjectHoldersResponse, InstantiateMsg, QueryMsg,
+        AirdropClaimResponse, AllowanceResponse, BalanceAtHeightResponse, CircuitBreakerResponse,
+        ExecuteMsg, FeeScheduleResponse, GetPriceResponse, GetShareBalanceResponse,
+        GetSubjectHoldersResponse, HasRoleResponse, InstantiateMsg, IsBlockedResponse,
+        LaunchProtectionResponse, LockedWeightResponse, LocksResponse, MarketStatusResponse,
+        MigrateMsg, OperatorsResponse, PendingConfigChangesResponse, PendingHolderRewardsResponse,
+        PollResponse, PollVoteResponse, ProtocolFeeRecipientsResponse, QueryMsg,
+        ReferralTierResponse, SelfVestingResponse, SolvencyResponse, SupplyAtHeightResponse,
+        TradeOp,
     },
+    migrate::migrate_state,
     owner::execute::{
-        set_fee_destination, set_protocol_buy_fee_percent, set_protocol_sell_fee_percent,
-        set_referral_buy_fee_percent, set_referral_sell_fee_percent, set_subject_buy_fee_percent,
-        set_subject_sell_fee_percent,
+        cancel_config_change, execute_config_change, grant_role, propose_config_change,
+        renounce_role, reset_circuit_breaker, revoke_role, set_market_status, set_transfers_enabled,
+        sweep_surplus, update_blocklist,
+    },
+    poll::{
+        execute::{create_poll, vote},
+        query::{get_poll, get_poll_vote},
+    },
+    state::{FeeRecipient, Role, State, TradingMode, ROLES, SNAPSHOT_START_HEIGHT, STATE},
+    user::execute::{
+        approve_operator, batch_trade, buy_shares, buy_shares_for, claim_airdrop,
+        claim_holder_rewards, decrease_allowance, fund_holder_airdrop, increase_allowance,
+        lock_shares, reclaim_airdrop, revoke_operator, sell_shares, sell_shares_for,
+        sell_shares_from, set_launch_protection, set_self_vesting, transfer_shares,
+        transfer_shares_from, unlock_shares, update_launch_allowlist,
     },
-    state::{State, STATE},
-    user::execute::{buy_shares, sell_shares},
     user::query::get_price_query,
     ContractError,
 };
 use crate::{
-    owner::execute::toggle_trading,
-    user::query::{get_share_balance, get_state, get_subject_holders},
+    owner::execute::set_trading_mode,
+    user::query::{
+        get_airdrop, get_airdrop_claim, get_allowance, get_balance_at_height, get_circuit_breaker,
+        get_fee_schedule, get_has_role, get_is_blocked, get_launch_protection, get_locked_weight,
+        get_locks, get_market_status_query, get_operators, get_pending_config_changes,
+        get_pending_holder_rewards, get_protocol_fee_recipients, get_referral_tier,
+        get_self_vesting, get_share_balance, get_solvency, get_state, get_subject_holders,
+        get_supply_at_height, get_trade_limits,
+    },
+    util::{ensure_deadline, TOTAL_FEE_WEIGHT},
 };
-use cosmwasm_std::{entry_point, to_json_binary, Binary, Deps, Event, StdResult, Uint128};
+use cosmwasm_std::{entry_point, to_json_binary, Binary, Deps, Event, StdError, StdResult, Uint128};
 use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
-use cw2::set_contract_version;
+use cw2::{get_contract_version, set_contract_version};
 
 // version info for migration info
 const CONTRACT_NAME: &str = "crates.io:my-first-contract";
@@ -33,7 +59,7 @@ const REFERRAL_BUY_FEE_PERCENT: Uint128 = Uint128::new(500); // 0.500%
 const REFERRAL_SELL_FEE_PERCENT: Uint128 = Uint128::new(0); // 0.000%
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn instantiate(deps: DepsMut, _env: Env, info: MessageInfo, msg: InstantiateMsg,) -> Result<Response, ContractError> {
+pub fn instantiate(deps: DepsMut, env: Env, info: MessageInfo, msg: InstantiateMsg,) -> Result<Response, ContractError> {
     let state = State {
         owner: info.sender.clone(),
         subject_buy_fee_percent: SUBJECT_BUY_FEE_PERCENT,
@@ -42,12 +68,19 @@ pub fn instantiate(deps: DepsMut, _env: Env, info: MessageInfo, msg: Instantiate
         protocol_sell_fee_percent: PROTOCOL_SELL_FEE_PERCENT,
         referral_buy_fee_percent: REFERRAL_BUY_FEE_PERCENT, 
         referral_sell_fee_percent: REFERRAL_SELL_FEE_PERCENT,
-        protocol_fee_destination: info.sender.clone(), // change later
-        trading_is_enabled: true,
+        protocol_fee_recipients: vec![FeeRecipient {
+            address: info.sender.clone(), // change later
+            weight: TOTAL_FEE_WEIGHT,
+        }],
+        trading_mode: TradingMode::Open,
     };
 
     set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
     STATE.save(deps.storage, &state)?;
+    for role in [Role::Admin, Role::Pauser, Role::FeeManager] {
+        ROLES.save(deps.storage, (role.as_str(), &info.sender), &true)?;
+    }
+    SNAPSHOT_START_HEIGHT.save(deps.storage, &env.block.height)?;
 
     Ok(Response::new()
         .add_event(Event::new("contract_instantiated"))
@@ -64,67 +97,208 @@ pub fn instantiate(deps: DepsMut, _env: Env, info: MessageInfo, msg: Instantiate
 #[cfg_attr(not(feature = "library"), entry_point)]
 pub fn execute(
     deps: DepsMut,
-    _env: Env,
+    env: Env,
     info: MessageInfo,
     msg: ExecuteMsg,
 ) -> Result<Response, ContractError> {
     let state: State = STATE.load(deps.storage)?;
     match msg {
-        ExecuteMsg::SetFeeDestination { fee_destination } => {
-            set_fee_destination(deps, info, fee_destination)
-        }
-        ExecuteMsg::SetProtocolBuyFeePercent {
-            protocol_buy_fee_percent,
-        } => set_protocol_buy_fee_percent(deps, info, protocol_buy_fee_percent),
-        ExecuteMsg::SetProtocolSellFeePercent {
-            protocol_sell_fee_percent,
-        } => set_protocol_sell_fee_percent(deps, info, protocol_sell_fee_percent),
-        ExecuteMsg::SetSubjectBuyFeePercent {
-            subject_buy_fee_percent,
-        } => set_subject_buy_fee_percent(deps, info, subject_buy_fee_percent),
-        ExecuteMsg::SetSubjectSellFeePercent {
-            subject_sell_fee_percent,
-        } => set_subject_sell_fee_percent(deps, info, subject_sell_fee_percent),
-        ExecuteMsg::SetReferralBuyFeePercent {
-            referral_buy_fee_percent,
-        } => set_referral_buy_fee_percent(deps, info, referral_buy_fee_percent),
-        ExecuteMsg::SetReferralSellFeePercent {
-            referral_sell_fee_percent,
-        } => set_referral_sell_fee_percent(deps, info, referral_sell_fee_percent),
         ExecuteMsg::BuyShares {
             shares_subject,
             referral,
+            recipient,
+            deadline,
         } => {
-            if state.trading_is_enabled == false {
-                return Err(ContractError::TradingIsDisabled {});
+            ensure_deadline(&env.block, deadline)?;
+            match state.trading_mode {
+                TradingMode::Open => {}
+                TradingMode::SellOnly => return Err(ContractError::TradingIsSellOnly {}),
+                TradingMode::Halted => return Err(ContractError::TradingIsDisabled {}),
             }
-            buy_shares(deps, info, shares_subject, referral)
+            let recipient = match recipient {
+                Some(recipient) => deps.api.addr_validate(recipient.as_str())?,
+                None => info.sender.clone(),
+            };
+            let payer = info.sender.clone();
+            buy_shares(deps, env, info, recipient, payer, shares_subject, referral)
         }
         ExecuteMsg::SellShares {
             shares_subject,
             referral,
+            proceeds_recipient,
+            deadline,
         } => {
-            if state.trading_is_enabled == false {
+            ensure_deadline(&env.block, deadline)?;
+            if state.trading_mode == TradingMode::Halted {
                 return Err(ContractError::TradingIsDisabled {});
             }
-            sell_shares(deps, info, shares_subject, referral)
+            let proceeds_recipient = match proceeds_recipient {
+                Some(proceeds_recipient) => deps.api.addr_validate(proceeds_recipient.as_str())?,
+                None => info.sender.clone(),
+            };
+            sell_shares(deps, env, info.sender, proceeds_recipient, shares_subject, referral)
+        }
+        ExecuteMsg::SetTradingMode { mode } => set_trading_mode(deps, info, mode),
+        ExecuteMsg::ClaimHolderRewards { shares_subject } => {
+            claim_holder_rewards(deps, info, shares_subject)
+        }
+        ExecuteMsg::FundHolderAirdrop {
+            shares_subject,
+            expires,
+        } => fund_holder_airdrop(deps, env, info, shares_subject, expires),
+        ExecuteMsg::ClaimAirdrop { id } => claim_airdrop(deps, env, info, id),
+        ExecuteMsg::ReclaimAirdrop { id } => reclaim_airdrop(deps, env, info, id),
+        ExecuteMsg::LockShares {
+            shares_subject,
+            amount,
+            duration,
+        } => lock_shares(deps, env, info, shares_subject, amount, duration),
+        ExecuteMsg::UnlockShares { id } => unlock_shares(deps, env, info, id),
+        ExecuteMsg::SetSelfVesting {
+            amount,
+            cliff,
+            duration,
+        } => set_self_vesting(deps, env, info, amount, cliff, duration),
+        ExecuteMsg::CreatePoll {
+            question,
+            options,
+            end_time,
+        } => create_poll(deps, env, info, question, options, end_time),
+        ExecuteMsg::Vote { poll_id, option } => vote(deps, env, info, poll_id, option),
+        ExecuteMsg::GrantRole { role, account } => grant_role(deps, info, role, account),
+        ExecuteMsg::RevokeRole { role, account } => revoke_role(deps, info, role, account),
+        ExecuteMsg::RenounceRole { role } => renounce_role(deps, info, role),
+        ExecuteMsg::ProposeConfigChange { change } => {
+            propose_config_change(deps, env, info, change)
+        }
+        ExecuteMsg::ExecuteConfigChange { id } => execute_config_change(deps, env, info, id),
+        ExecuteMsg::CancelConfigChange { id } => cancel_config_change(deps, info, id),
+        ExecuteMsg::SetMarketStatus {
+            shares_subject,
+            status,
+        } => set_market_status(deps, info, shares_subject, status),
+        ExecuteMsg::SweepSurplus { denom, recipient } => {
+            sweep_surplus(deps, env, info, denom, recipient)
+        }
+        ExecuteMsg::TransferShares {
+            shares_subject,
+            recipient,
+            amount,
+        } => transfer_shares(deps, env, info, shares_subject, recipient, amount),
+        ExecuteMsg::SetTransfersEnabled {
+            shares_subject,
+            enabled,
+        } => set_transfers_enabled(deps, info, shares_subject, enabled),
+        ExecuteMsg::IncreaseAllowance {
+            spender,
+            shares_subject,
+            amount,
+            expires,
+        } => increase_allowance(deps, env, info, spender, shares_subject, amount, expires),
+        ExecuteMsg::DecreaseAllowance {
+            spender,
+            shares_subject,
+            amount,
+            expires,
+        } => decrease_allowance(deps, env, info, spender, shares_subject, amount, expires),
+        ExecuteMsg::TransferSharesFrom {
+            owner,
+            shares_subject,
+            recipient,
+            amount,
+        } => transfer_shares_from(deps, env, info, owner, shares_subject, recipient, amount),
+        ExecuteMsg::SellSharesFrom {
+            owner,
+            shares_subject,
+            referral,
+            deadline,
+        } => {
+            ensure_deadline(&env.block, deadline)?;
+            if state.trading_mode == TradingMode::Halted {
+                return Err(ContractError::TradingIsDisabled {});
+            }
+            sell_shares_from(deps, env, info, owner, shares_subject, referral)
+        }
+        ExecuteMsg::ApproveOperator { operator, expires } => {
+            approve_operator(deps, env, info, operator, expires)
+        }
+        ExecuteMsg::RevokeOperator { operator } => revoke_operator(deps, info, operator),
+        ExecuteMsg::BuySharesFor {
+            owner,
+            shares_subject,
+            referral,
+            deadline,
+        } => {
+            ensure_deadline(&env.block, deadline)?;
+            match state.trading_mode {
+                TradingMode::Open => {}
+                TradingMode::SellOnly => return Err(ContractError::TradingIsSellOnly {}),
+                TradingMode::Halted => return Err(ContractError::TradingIsDisabled {}),
+            }
+            buy_shares_for(deps, env, info, owner, shares_subject, referral)
+        }
+        ExecuteMsg::SellSharesFor {
+            owner,
+            shares_subject,
+            referral,
+            deadline,
+        } => {
+            ensure_deadline(&env.block, deadline)?;
+            if state.trading_mode == TradingMode::Halted {
+                return Err(ContractError::TradingIsDisabled {});
+            }
+            sell_shares_for(deps, env, info, owner, shares_subject, referral)
+        }
+        ExecuteMsg::BatchTrade { trades, deadline } => {
+            ensure_deadline(&env.block, deadline)?;
+            match state.trading_mode {
+                TradingMode::Open => {}
+                TradingMode::SellOnly => {
+                    if trades.iter().any(|trade| matches!(trade, TradeOp::Buy { .. })) {
+                        return Err(ContractError::TradingIsSellOnly {});
+                    }
+                }
+                TradingMode::Halted => return Err(ContractError::TradingIsDisabled {}),
+            }
+            batch_trade(deps, env, info, trades)
+        }
+        ExecuteMsg::SetLaunchProtection {
+            window,
+            max_shares_per_address,
+            min_buy_interval,
+            allowlist_only,
+        } => set_launch_protection(
+            deps,
+            info,
+            window,
+            max_shares_per_address,
+            min_buy_interval,
+            allowlist_only,
+        ),
+        ExecuteMsg::UpdateLaunchAllowlist { add, remove } => {
+            update_launch_allowlist(deps, info, add, remove)
+        }
+        ExecuteMsg::ResetCircuitBreaker { shares_subject } => {
+            reset_circuit_breaker(deps, info, shares_subject)
         }
-        ExecuteMsg::ToggleTrading { is_enabled } => toggle_trading(deps, info, is_enabled),
+        ExecuteMsg::UpdateBlocklist { add, remove } => update_blocklist(deps, info, add, remove),
     }
 }
 
 #[cfg_attr(not(feature = "library"), entry_point)]
-pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
     match msg {
         QueryMsg::GetPrice {
             shares_subject,
             with_fees,
             is_buy,
+            referral,
         } => to_json_binary::<GetPriceResponse>(&get_price_query(
             deps,
             shares_subject,
             with_fees,
             is_buy,
+            referral,
         )?),
         QueryMsg::GetShareBalance {
             shares_subject,
@@ -138,5 +312,111 @@ pub fn query(deps: Deps, _env: Env, msg: QueryMsg) -> StdResult<Binary> {
         QueryMsg::GetSubjectHolders { shares_subject } => {
             to_json_binary::<GetSubjectHoldersResponse>(&get_subject_holders(deps, shares_subject)?)
         }
+        QueryMsg::ReferralTier { referrer } => {
+            to_json_binary::<ReferralTierResponse>(&get_referral_tier(deps, referrer)?)
+        }
+        QueryMsg::FeeSchedule { shares_subject } => {
+            to_json_binary::<FeeScheduleResponse>(&get_fee_schedule(deps, shares_subject)?)
+        }
+        QueryMsg::ProtocolFeeRecipients {} => {
+            to_json_binary::<ProtocolFeeRecipientsResponse>(&get_protocol_fee_recipients(deps)?)
+        }
+        QueryMsg::HasRole { role, account } => {
+            to_json_binary::<HasRoleResponse>(&get_has_role(deps, role, account)?)
+        }
+        QueryMsg::PendingConfigChanges {} => {
+            to_json_binary::<PendingConfigChangesResponse>(&get_pending_config_changes(deps)?)
+        }
+        QueryMsg::MarketStatus { shares_subject } => {
+            to_json_binary::<MarketStatusResponse>(&get_market_status_query(deps, shares_subject)?)
+        }
+        QueryMsg::Solvency {} => to_json_binary::<SolvencyResponse>(&get_solvency(deps, env)?),
+        QueryMsg::Allowance {
+            owner,
+            spender,
+            shares_subject,
+        } => to_json_binary::<AllowanceResponse>(&get_allowance(
+            deps,
+            owner,
+            spender,
+            shares_subject,
+        )?),
+        QueryMsg::Operators { owner } => {
+            to_json_binary::<OperatorsResponse>(&get_operators(deps, env, owner)?)
+        }
+        QueryMsg::LaunchProtection { shares_subject } => to_json_binary::<LaunchProtectionResponse>(
+            &get_launch_protection(deps, env, shares_subject)?,
+        ),
+        QueryMsg::TradeLimits {} => to_json_binary(&get_trade_limits(deps)?),
+        QueryMsg::CircuitBreaker { shares_subject } => to_json_binary::<CircuitBreakerResponse>(
+            &get_circuit_breaker(deps, env, shares_subject)?,
+        ),
+        QueryMsg::IsBlocked { address } => {
+            to_json_binary::<IsBlockedResponse>(&get_is_blocked(deps, address)?)
+        }
+        QueryMsg::BalanceAtHeight {
+            shares_subject,
+            address,
+            height,
+        } => to_json_binary::<BalanceAtHeightResponse>(&get_balance_at_height(
+            deps,
+            env,
+            shares_subject,
+            address,
+            height,
+        )?),
+        QueryMsg::SupplyAtHeight {
+            shares_subject,
+            height,
+        } => to_json_binary::<SupplyAtHeightResponse>(&get_supply_at_height(
+            deps,
+            env,
+            shares_subject,
+            height,
+        )?),
+        QueryMsg::PendingHolderRewards {
+            shares_subject,
+            holder,
+        } => to_json_binary::<PendingHolderRewardsResponse>(&get_pending_holder_rewards(
+            deps,
+            shares_subject,
+            holder,
+        )?),
+        QueryMsg::Airdrop { id } => to_json_binary(&get_airdrop(deps, id)?),
+        QueryMsg::AirdropClaim { id, holder } => to_json_binary::<AirdropClaimResponse>(
+            &get_airdrop_claim(deps, env, id, holder)?,
+        ),
+        QueryMsg::Locks { owner } => to_json_binary::<LocksResponse>(&get_locks(deps, owner)?),
+        QueryMsg::LockedWeight { shares_subject } => {
+            to_json_binary::<LockedWeightResponse>(&get_locked_weight(deps, env, shares_subject)?)
+        }
+        QueryMsg::SelfVesting { shares_subject } => to_json_binary::<SelfVestingResponse>(
+            &get_self_vesting(deps, env, shares_subject)?,
+        ),
+        QueryMsg::Poll { id } => to_json_binary::<PollResponse>(&get_poll(deps, env, id)?),
+        QueryMsg::PollVote { id, voter } => {
+            to_json_binary::<PollVoteResponse>(&get_poll_vote(deps, id, voter)?)
+        }
     }
 }
+
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn migrate(deps: DepsMut, env: Env, _msg: MigrateMsg) -> Result<Response, ContractError> {
+    let version = get_contract_version(deps.storage)?;
+    if version.contract != CONTRACT_NAME {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot migrate from a different contract",
+        )));
+    }
+    let reserves_migrated = migrate_state(deps.storage, env.block.height)?;
+    set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("contract_migrated")
+                .add_attribute("reserves_migrated", reserves_migrated.to_string()),
+        )
+        .add_attribute("method", "migrate")
+        .add_attribute("from_version", version.version)
+        .add_attribute("to_version", CONTRACT_VERSION))
+}
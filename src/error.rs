@@ -12,9 +12,132 @@ pub enum ContractError {
     #[error("Trading is disabled")]
     TradingIsDisabled {},
 
+    #[error("Trading is in sell-only mode")]
+    TradingIsSellOnly {},
+
     #[error("Invalid token sent")]
     InvalidTokenSentPayment {},
 
     #[error("The tradingstate is the same")]
     TradingStateTheSame {},
+
+    #[error("Config change not found")]
+    ConfigChangeNotFound {},
+
+    #[error("Config change cannot be executed before {eta}")]
+    ConfigChangeNotReady { eta: String },
+
+    #[error("Config change expired at {expires_at}")]
+    ConfigChangeExpired { expires_at: String },
+
+    #[error("Buying is disabled for this market ({status})")]
+    MarketBuysDisabled { status: String },
+
+    #[error("Selling is disabled for this market ({status})")]
+    MarketSellsDisabled { status: String },
+
+    #[error("Market is delisted")]
+    MarketDelisted {},
+
+    #[error("Insufficient reserve to pay out the sell")]
+    InsufficientReserve {},
+
+    #[error("Reserve migration is unfinished, run migrate again to continue it")]
+    ReserveMigrationPending {},
+
+    #[error("No surplus to sweep")]
+    NoSurplus {},
+
+    #[error("Share transfers are disabled for this subject")]
+    TransfersDisabled {},
+
+    #[error("Insufficient allowance")]
+    InsufficientAllowance {},
+
+    #[error("Allowance is expired")]
+    AllowanceExpired {},
+
+    #[error("Invalid expiration")]
+    InvalidExpiration {},
+
+    #[error("Operator is not approved")]
+    OperatorNotApproved {},
+
+    #[error("Insufficient payment")]
+    InsufficientPayment {},
+
+    #[error("Trade exceeds the slippage bounds")]
+    SlippageExceeded {},
+
+    #[error("Trade deadline exceeded")]
+    DeadlineExceeded {},
+
+    #[error("Buyer is not on the launch allowlist")]
+    LaunchNotAllowlisted {},
+
+    #[error("Launch purchase cap reached")]
+    LaunchCapReached {},
+
+    #[error("Launch buy interval not elapsed")]
+    LaunchBuyTooSoon {},
+
+    #[error("Trade cooldown active for another {blocks_remaining} blocks")]
+    TradeCooldown { blocks_remaining: u64 },
+
+    #[error("Too many trades in this block")]
+    TradeRateLimited {},
+
+    #[error("Circuit breaker halted trading until block {until_height}")]
+    CircuitBreakerActive { until_height: u64 },
+
+    #[error("Address {address} is blocked")]
+    AddressBlocked { address: String },
+
+    #[error("Screening contract is unavailable")]
+    ScreeningUnavailable {},
+
+    #[error("No holder rewards to claim")]
+    NoHolderRewards {},
+
+    #[error("Airdrop not found")]
+    AirdropNotFound {},
+
+    #[error("Airdrop has expired")]
+    AirdropExpired {},
+
+    #[error("Airdrop has not expired yet")]
+    AirdropNotExpired {},
+
+    #[error("Nothing to claim from this airdrop")]
+    NoAirdropShare {},
+
+    #[error("{locked} shares are locked")]
+    SharesLocked { locked: String },
+
+    #[error("Lock not found")]
+    LockNotFound {},
+
+    #[error("Lock cannot be released before {unlocks_at}")]
+    LockNotExpired { unlocks_at: String },
+
+    #[error("{unvested} shares are still vesting")]
+    SharesNotVested { unvested: String },
+
+    #[error("Poll not found")]
+    PollNotFound {},
+
+    #[error("Poll is closed")]
+    PollClosed {},
+
+    #[error("Already voted on this poll")]
+    AlreadyVoted {},
+
+    #[error("Invalid poll option")]
+    InvalidPollOption {},
+
+    #[error("No shares at the start of the poll")]
+    NoVotingPower {},
+
+    #[error("Batch trades are limited to {max_per_op} shares per op and {max_total} in total")]
+    BatchTooLarge { max_per_op: String, max_total: String },
 }
\ No newline at end of file
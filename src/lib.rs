@@ -1,10 +1,12 @@
 pub mod contract;
 mod error;
 pub mod helpers;
+mod migrate;
 pub mod msg;
 pub mod state;
 mod user;
 mod owner;
+mod poll;
 mod util;
 
 pub use crate::error::ContractError;
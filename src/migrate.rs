@@ -0,0 +1,143 @@
+use cosmwasm_std::{Addr, Order, StdError, StdResult, Storage, Uint128};
+use cw_storage_plus::{Bound, Item};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    state::{
+        FeeRecipient, ReserveMigration, Role, State, TradingMode, RESERVE, RESERVE_MIGRATION, ROLES,
+        SHARES_SUPPLY, SNAPSHOT_START_HEIGHT, STATE, TOTAL_RESERVE,
+    },
+    util::{get_price, TOTAL_FEE_WEIGHT},
+};
+
+// Superset of every State layout that was deployed so far. Fields that were
+// renamed or replaced are optional and converted in migrate_state_layout.
+#[derive(Serialize, Deserialize)]
+struct LegacyState {
+    owner: Addr,
+    subject_buy_fee_percent: Uint128,
+    subject_sell_fee_percent: Uint128,
+    protocol_buy_fee_percent: Uint128,
+    protocol_sell_fee_percent: Uint128,
+    referral_buy_fee_percent: Uint128,
+    referral_sell_fee_percent: Uint128,
+    // Replaced by protocol_fee_recipients
+    protocol_fee_destination: Option<Addr>,
+    protocol_fee_recipients: Option<Vec<FeeRecipient>>,
+    // Replaced by trading_mode
+    trading_is_enabled: Option<bool>,
+    trading_mode: Option<TradingMode>,
+}
+
+const LEGACY_STATE: Item<LegacyState> = Item::new("state");
+// Prices summed per migrate call, a larger rebuild continues on the next call
+const RESERVE_MIGRATION_BATCH: u128 = 1000;
+
+// Returns false while the reserve rebuild is unfinished, migrate has to run again to continue it
+pub fn migrate_state(storage: &mut dyn Storage, height: u64) -> StdResult<bool> {
+    migrate_state_layout(storage)?;
+    migrate_roles(storage)?;
+    let reserves_migrated = migrate_reserves(storage)?;
+    migrate_snapshots(storage, height)?;
+    Ok(reserves_migrated)
+}
+
+fn migrate_state_layout(storage: &mut dyn Storage) -> StdResult<()> {
+    let legacy = LEGACY_STATE.load(storage)?;
+    let protocol_fee_recipients = match (legacy.protocol_fee_recipients, legacy.protocol_fee_destination) {
+        (Some(recipients), _) => recipients,
+        (None, Some(destination)) => vec![FeeRecipient {
+            address: destination,
+            weight: TOTAL_FEE_WEIGHT,
+        }],
+        (None, None) => return Err(StdError::generic_err("Missing protocol fee recipients")),
+    };
+    let trading_mode = match (legacy.trading_mode, legacy.trading_is_enabled) {
+        (Some(mode), _) => mode,
+        (None, Some(true)) => TradingMode::Open,
+        (None, Some(false)) => TradingMode::Halted,
+        (None, None) => return Err(StdError::generic_err("Missing trading mode")),
+    };
+    let state = State {
+        owner: legacy.owner,
+        subject_buy_fee_percent: legacy.subject_buy_fee_percent,
+        subject_sell_fee_percent: legacy.subject_sell_fee_percent,
+        protocol_buy_fee_percent: legacy.protocol_buy_fee_percent,
+        protocol_sell_fee_percent: legacy.protocol_sell_fee_percent,
+        referral_buy_fee_percent: legacy.referral_buy_fee_percent,
+        referral_sell_fee_percent: legacy.referral_sell_fee_percent,
+        protocol_fee_recipients,
+        trading_mode,
+    };
+    STATE.save(storage, &state)
+}
+
+// Contracts deployed before role based access control give every role to the owner
+fn migrate_roles(storage: &mut dyn Storage) -> StdResult<()> {
+    let has_admin = ROLES
+        .prefix(Role::Admin.as_str())
+        .keys(storage, None, None, Order::Ascending)
+        .next()
+        .is_some();
+    if has_admin {
+        return Ok(());
+    }
+    let state = STATE.load(storage)?;
+    for role in [Role::Admin, Role::Pauser, Role::FeeManager] {
+        ROLES.save(storage, (role.as_str(), &state.owner), &true)?;
+    }
+    Ok(())
+}
+
+// Contracts deployed before reserve accounting rebuild the reserve from the supply,
+// every share was bought at get_price of the supply it brought the market to.
+// At most RESERVE_MIGRATION_BATCH prices are summed per call, subjects in ascending order.
+fn migrate_reserves(storage: &mut dyn Storage) -> StdResult<bool> {
+    let mut migration = match RESERVE_MIGRATION.may_load(storage)? {
+        Some(migration) => migration,
+        None if TOTAL_RESERVE.may_load(storage)?.is_some() => return Ok(true),
+        None => ReserveMigration {
+            shares_subject: next_supply_subject(storage, None)?,
+            ..ReserveMigration::default()
+        },
+    };
+    let mut budget = RESERVE_MIGRATION_BATCH;
+    while let Some(shares_subject) = migration.shares_subject.clone() {
+        let supply = SHARES_SUPPLY.may_load(storage, &shares_subject)?.unwrap_or_default();
+        let summed = migration.supply_summed.u128();
+        let end = supply.u128().min(summed + budget);
+        for supply_after_buy in summed + 1..=end {
+            migration.reserve += get_price(Uint128::new(supply_after_buy));
+        }
+        budget -= end - summed;
+        migration.supply_summed = Uint128::new(end);
+        if end < supply.u128() {
+            RESERVE_MIGRATION.save(storage, &migration)?;
+            return Ok(false);
+        }
+        RESERVE.save(storage, &shares_subject, &migration.reserve)?;
+        migration.total_reserve += migration.reserve;
+        migration.shares_subject = next_supply_subject(storage, Some(&shares_subject))?;
+        migration.supply_summed = Uint128::zero();
+        migration.reserve = Uint128::zero();
+    }
+    RESERVE_MIGRATION.remove(storage);
+    TOTAL_RESERVE.save(storage, &migration.total_reserve)?;
+    Ok(true)
+}
+
+fn next_supply_subject(storage: &dyn Storage, after: Option<&Addr>) -> StdResult<Option<Addr>> {
+    SHARES_SUPPLY
+        .keys(storage, after.map(Bound::exclusive), None, Order::Ascending)
+        .next()
+        .transpose()
+}
+
+// Balances and supplies kept their primary namespaces when they became snapshot maps, so
+// existing values are read as is and only the height history starts from here
+fn migrate_snapshots(storage: &mut dyn Storage, height: u64) -> StdResult<()> {
+    if SNAPSHOT_START_HEIGHT.may_load(storage)?.is_some() {
+        return Ok(());
+    }
+    SNAPSHOT_START_HEIGHT.save(storage, &height)
+}
@@ -1,14 +1,112 @@
-use cosmwasm_std::{Addr, Uint128};
+use cosmwasm_std::{Addr, Timestamp, Uint128};
 use cosmwasm_schema::{cw_serde, QueryResponses};
+use cw_utils::{Duration, Expiration};
 
-use crate::state::State;
+use crate::state::{
+    Airdrop, CircuitBreaker, CircuitBreakerConfig, FeeRecipient, LaunchProtection, MarketStatus,
+    PendingConfigChange, Poll, PollVote, ReferralTier, Role, SelfVesting, ShareLock, State, TradeLimits, TradingMode,
+};
 
 #[cw_serde]
 pub struct InstantiateMsg {
 }
 
+#[cw_serde]
+pub struct MigrateMsg {
+}
+
 #[cw_serde]
 pub enum ExecuteMsg {
+    BuyShares {
+        shares_subject: Addr,
+        referral: Addr,
+        recipient: Option<Addr>,
+        deadline: Option<Expiration>,
+    },
+    SellShares {
+        shares_subject: Addr,
+        referral: Addr,
+        proceeds_recipient: Option<Addr>,
+        deadline: Option<Expiration>,
+    },
+    SetTradingMode { mode: TradingMode },
+    GrantRole { role: Role, account: Addr },
+    RevokeRole { role: Role, account: Addr },
+    RenounceRole { role: Role },
+    ProposeConfigChange { change: ConfigChange },
+    ExecuteConfigChange { id: u64 },
+    CancelConfigChange { id: u64 },
+    SetMarketStatus { shares_subject: Addr, status: MarketStatus },
+    SweepSurplus { denom: String, recipient: Addr },
+    TransferShares { shares_subject: Addr, recipient: Addr, amount: Uint128 },
+    SetTransfersEnabled { shares_subject: Addr, enabled: bool },
+    IncreaseAllowance {
+        spender: Addr,
+        shares_subject: Addr,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    DecreaseAllowance {
+        spender: Addr,
+        shares_subject: Addr,
+        amount: Uint128,
+        expires: Option<Expiration>,
+    },
+    TransferSharesFrom { owner: Addr, shares_subject: Addr, recipient: Addr, amount: Uint128 },
+    SellSharesFrom {
+        owner: Addr,
+        shares_subject: Addr,
+        referral: Addr,
+        deadline: Option<Expiration>,
+    },
+    ApproveOperator { operator: Addr, expires: Option<Expiration> },
+    RevokeOperator { operator: Addr },
+    BuySharesFor {
+        owner: Addr,
+        shares_subject: Addr,
+        referral: Addr,
+        deadline: Option<Expiration>,
+    },
+    SellSharesFor {
+        owner: Addr,
+        shares_subject: Addr,
+        referral: Addr,
+        deadline: Option<Expiration>,
+    },
+    BatchTrade { trades: Vec<TradeOp>, deadline: Option<Expiration> },
+    SetLaunchProtection {
+        window: Duration,
+        max_shares_per_address: Option<Uint128>,
+        min_buy_interval: Option<Duration>,
+        allowlist_only: bool,
+    },
+    UpdateLaunchAllowlist { add: Vec<Addr>, remove: Vec<Addr> },
+    ResetCircuitBreaker { shares_subject: Addr },
+    UpdateBlocklist { add: Vec<Addr>, remove: Vec<Addr> },
+    ClaimHolderRewards { shares_subject: Addr },
+    FundHolderAirdrop { shares_subject: Addr, expires: Expiration },
+    ClaimAirdrop { id: u64 },
+    ReclaimAirdrop { id: u64 },
+    // Duration in seconds, up to a year
+    LockShares { shares_subject: Addr, amount: Uint128, duration: u64 },
+    UnlockShares { id: u64 },
+    // Cliff and duration in seconds, a cliff equal to the duration vests all at once
+    SetSelfVesting { amount: Uint128, cliff: u64, duration: u64 },
+    CreatePoll { question: String, options: Vec<String>, end_time: Timestamp },
+    // `option` is the index into the poll options
+    Vote { poll_id: u64, option: u32 },
+}
+
+// Slippage bounds cover the whole op, fees included
+#[cw_serde]
+pub enum TradeOp {
+    Buy { shares_subject: Addr, referral: Addr, amount: Uint128, max_cost: Uint128 },
+    Sell { shares_subject: Addr, referral: Addr, amount: Uint128, min_proceeds: Uint128 },
+}
+
+// Config changes only apply after the timelock delay, see ProposeConfigChange
+#[cw_serde]
+pub enum ConfigChange {
     SetFeeDestination { fee_destination: Addr },
     SetProtocolBuyFeePercent { protocol_buy_fee_percent: Uint128 },
     SetProtocolSellFeePercent { protocol_sell_fee_percent: Uint128 },
@@ -16,22 +114,86 @@ pub enum ExecuteMsg {
     SetSubjectSellFeePercent { subject_sell_fee_percent: Uint128 },
     SetReferralBuyFeePercent { referral_buy_fee_percent: Uint128 },
     SetReferralSellFeePercent { referral_sell_fee_percent: Uint128 },
-    BuyShares { shares_subject: Addr, referral: Addr },
-    SellShares { shares_subject: Addr, referral: Addr },
-    ToggleTrading { is_enabled: bool },
+    SetReferralTiers { tiers: Vec<ReferralTier> },
+    SetSubjectFeeBounds { min_fee_percent: Uint128, max_fee_percent: Uint128 },
+    SetProtocolFeeRecipients { recipients: Vec<FeeRecipient> },
+    SetConfigChangeDelay { delay: u64 },
+    SetTradeLimits { buy_sell_cooldown_blocks: u64, max_trades_per_block: u32 },
+    SetCircuitBreaker { max_price_move_percent: Uint128, window_blocks: u64, cooldown_blocks: u64 },
+    SetScreeningContract { contract: Option<Addr> },
+    // Subject settings apply to the proposer's own market, only the proposer executes or cancels
+    SetMySubjectFee { buy: Uint128, sell: Uint128 },
+    SetHolderRewardPercent { percent: Uint128 },
 }
 
 #[cw_serde]
 #[derive(QueryResponses)]
 pub enum QueryMsg {
     #[returns(GetPriceResponse)]
-    GetPrice { shares_subject: Addr, with_fees: bool, is_buy: bool },
+    // Pass the referral to quote its tier fee, the global referral fee applies otherwise
+    GetPrice { shares_subject: Addr, with_fees: bool, is_buy: bool, referral: Option<Addr> },
     #[returns(GetShareBalanceResponse)]
     GetShareBalance { shares_subject: Addr, my_address: Addr },
     #[returns(State)]
     GetState { },
     #[returns(GetSubjectHoldersResponse)]
     GetSubjectHolders { shares_subject: Addr },
+    #[returns(ReferralTierResponse)]
+    ReferralTier { referrer: Addr },
+    #[returns(FeeScheduleResponse)]
+    FeeSchedule { shares_subject: Addr },
+    #[returns(ProtocolFeeRecipientsResponse)]
+    ProtocolFeeRecipients { },
+    #[returns(HasRoleResponse)]
+    HasRole { role: Role, account: Addr },
+    #[returns(PendingConfigChangesResponse)]
+    PendingConfigChanges { },
+    #[returns(MarketStatusResponse)]
+    MarketStatus { shares_subject: Addr },
+    #[returns(SolvencyResponse)]
+    Solvency { },
+    #[returns(AllowanceResponse)]
+    Allowance { owner: Addr, spender: Addr, shares_subject: Addr },
+    #[returns(OperatorsResponse)]
+    Operators { owner: Addr },
+    #[returns(LaunchProtectionResponse)]
+    LaunchProtection { shares_subject: Addr },
+    #[returns(TradeLimits)]
+    TradeLimits { },
+    #[returns(CircuitBreakerResponse)]
+    CircuitBreaker { shares_subject: Addr },
+    #[returns(IsBlockedResponse)]
+    IsBlocked { address: Addr },
+    // Values at the start of block `height`, before any trade in that block
+    #[returns(BalanceAtHeightResponse)]
+    BalanceAtHeight { shares_subject: Addr, address: Addr, height: u64 },
+    #[returns(SupplyAtHeightResponse)]
+    SupplyAtHeight { shares_subject: Addr, height: u64 },
+    #[returns(PendingHolderRewardsResponse)]
+    PendingHolderRewards { shares_subject: Addr, holder: Addr },
+    #[returns(Airdrop)]
+    Airdrop { id: u64 },
+    #[returns(AirdropClaimResponse)]
+    AirdropClaim { id: u64, holder: Addr },
+    #[returns(LocksResponse)]
+    Locks { owner: Addr },
+    // Boosted weight of the locks that have not expired yet, released or not
+    #[returns(LockedWeightResponse)]
+    LockedWeight { shares_subject: Addr },
+    #[returns(SelfVestingResponse)]
+    SelfVesting { shares_subject: Addr },
+    #[returns(PollResponse)]
+    Poll { id: u64 },
+    #[returns(PollVoteResponse)]
+    PollVote { id: u64, voter: Addr },
+}
+
+// Interface the external screening contract has to implement
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum ScreeningQueryMsg {
+    #[returns(IsBlockedResponse)]
+    IsBlocked { address: String },
 }
 
 #[cw_serde]
@@ -45,4 +207,132 @@ pub struct GetShareBalanceResponse {
 #[cw_serde]
 pub struct GetSubjectHoldersResponse {
     pub amount: Uint128,
+}
+#[cw_serde]
+pub struct ReferralTierResponse {
+    pub volume: Uint128,
+    pub tier: Option<u32>,
+    pub buy_fee_percent: Uint128,
+    pub sell_fee_percent: Uint128,
+}
+#[cw_serde]
+pub struct FeeScheduleResponse {
+    pub subject_buy_fee_percent: Uint128,
+    pub subject_sell_fee_percent: Uint128,
+    pub protocol_buy_fee_percent: Uint128,
+    pub protocol_sell_fee_percent: Uint128,
+    pub referral_buy_fee_percent: Uint128,
+    pub referral_sell_fee_percent: Uint128,
+    pub is_custom: bool,
+}
+#[cw_serde]
+pub struct ProtocolFeeRecipientsResponse {
+    pub recipients: Vec<FeeRecipient>,
+}
+#[cw_serde]
+pub struct HasRoleResponse {
+    pub has_role: bool,
+}
+#[cw_serde]
+pub struct PendingConfigChangesResponse {
+    pub delay: u64,
+    pub changes: Vec<PendingConfigChange>,
+}
+#[cw_serde]
+pub struct MarketStatusResponse {
+    pub status: MarketStatus,
+    pub transfers_enabled: bool,
+}
+#[cw_serde]
+pub struct SolvencyResponse {
+    pub total_reserve: Uint128,
+    pub total_holder_rewards: Uint128,
+    pub total_airdrops: Uint128,
+    pub balance: Uint128,
+    pub surplus: Uint128,
+    pub deficit: Uint128,
+    pub is_solvent: bool,
+}
+#[cw_serde]
+pub struct AllowanceResponse {
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+#[cw_serde]
+pub struct OperatorInfo {
+    pub operator: Addr,
+    pub expires: Expiration,
+}
+#[cw_serde]
+pub struct OperatorsResponse {
+    pub operators: Vec<OperatorInfo>,
+}
+#[cw_serde]
+pub struct LaunchProtectionResponse {
+    pub protection: Option<LaunchProtection>,
+    pub is_active: bool,
+}
+#[cw_serde]
+pub struct CircuitBreakerResponse {
+    pub config: CircuitBreakerConfig,
+    pub breaker: Option<CircuitBreaker>,
+    pub is_halted: bool,
+}
+#[cw_serde]
+pub struct IsBlockedResponse {
+    pub is_blocked: bool,
+}
+#[cw_serde]
+pub struct BalanceAtHeightResponse {
+    pub balance: Uint128,
+    pub height: u64,
+}
+#[cw_serde]
+pub struct SupplyAtHeightResponse {
+    pub supply: Uint128,
+    pub height: u64,
+}
+#[cw_serde]
+pub struct PendingHolderRewardsResponse {
+    pub pending: Uint128,
+    pub reward_percent: Uint128,
+}
+#[cw_serde]
+pub struct AirdropClaimResponse {
+    pub claimable: Uint128,
+    pub claimed: Uint128,
+}
+#[cw_serde]
+pub struct LockInfo {
+    pub id: u64,
+    pub lock: ShareLock,
+}
+#[cw_serde]
+pub struct LocksResponse {
+    pub locks: Vec<LockInfo>,
+}
+#[cw_serde]
+pub struct LockedWeightResponse {
+    pub weight: Uint128,
+}
+#[cw_serde]
+pub struct SelfVestingResponse {
+    pub vesting: Option<SelfVesting>,
+    pub vested: Uint128,
+    pub unvested: Uint128,
+}
+#[cw_serde]
+pub enum PollStatus {
+    Open,
+    Closed,
+}
+#[cw_serde]
+pub struct PollResponse {
+    pub id: u64,
+    pub poll: Poll,
+    pub status: PollStatus,
+}
+#[cw_serde]
+pub struct PollVoteResponse {
+    pub vote: Option<PollVote>,
 }
\ No newline at end of file
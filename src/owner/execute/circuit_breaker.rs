@@ -0,0 +1,61 @@
+use cosmwasm_std::{Addr, DepsMut, Event, MessageInfo, Response, StdError, Uint128};
+
+use crate::{
+    state::{CircuitBreakerConfig, Role, CIRCUIT_BREAKERS, CIRCUIT_BREAKER_CONFIG},
+    util::ensure_role,
+    ContractError,
+};
+
+pub fn validate_circuit_breaker(
+    max_price_move_percent: Uint128,
+    window_blocks: u64,
+    cooldown_blocks: u64,
+) -> Result<(), ContractError> {
+    if !max_price_move_percent.is_zero() && (window_blocks == 0 || cooldown_blocks == 0) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Circuit breaker window and cooldown must be greater than zero",
+        )));
+    }
+    Ok(())
+}
+
+pub fn set_circuit_breaker(
+    deps: DepsMut,
+    info: MessageInfo,
+    max_price_move_percent: Uint128,
+    window_blocks: u64,
+    cooldown_blocks: u64,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Admin, &info.sender)?;
+    validate_circuit_breaker(max_price_move_percent, window_blocks, cooldown_blocks)?;
+    CIRCUIT_BREAKER_CONFIG.save(
+        deps.storage,
+        &CircuitBreakerConfig {
+            max_price_move_percent,
+            window_blocks,
+            cooldown_blocks,
+        },
+    )?;
+    Ok(Response::new().add_event(
+        Event::new("set_circuit_breaker")
+            .add_attribute("max_price_move_percent", max_price_move_percent)
+            .add_attribute("window_blocks", window_blocks.to_string())
+            .add_attribute("cooldown_blocks", cooldown_blocks.to_string()),
+    ))
+}
+
+// Lifts a halt early, the next trade starts a fresh reference window
+pub fn reset_circuit_breaker(
+    deps: DepsMut,
+    info: MessageInfo,
+    shares_subject: Addr,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Pauser, &info.sender)?;
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    CIRCUIT_BREAKERS.remove(deps.storage, &validated_shares_subject_address);
+    Ok(Response::new().add_event(
+        Event::new("reset_circuit_breaker")
+            .add_attribute("shares_subject", validated_shares_subject_address)
+            .add_attribute("sender", info.sender),
+    ))
+}
@@ -0,0 +1,249 @@
+use cosmwasm_std::{Addr, Deps, DepsMut, Env, Event, MessageInfo, Response, StdError, Storage};
+
+use crate::{
+    msg::ConfigChange,
+    state::{PendingConfigChange, Role, CONFIG_CHANGE_COUNT, CONFIG_CHANGE_DELAY, PENDING_CONFIG_CHANGES},
+    user::execute::{
+        set_holder_reward_percent, set_my_subject_fee, validate_holder_reward_percent,
+        validate_my_subject_fee,
+    },
+    util::{ensure_role, get_config_change_delay},
+    ContractError,
+};
+
+use super::{
+    set_circuit_breaker, set_fee_destination, set_protocol_buy_fee_percent,
+    set_protocol_fee_recipients, set_protocol_sell_fee_percent, set_referral_buy_fee_percent,
+    set_referral_sell_fee_percent, set_referral_tiers, set_screening_contract,
+    set_subject_buy_fee_percent, set_subject_fee_bounds, set_subject_sell_fee_percent,
+    set_trade_limits,
+    validate_circuit_breaker, validate_protocol_fee_percent, validate_protocol_fee_recipients,
+    validate_referral_fee_percent, validate_referral_tiers, validate_subject_fee_bounds,
+    validate_subject_fee_percent,
+};
+
+const MAX_CONFIG_CHANGE_DELAY: u64 = 30 * 86400;
+// A ready change left unexecuted this long is stale and has to be proposed again
+const CONFIG_CHANGE_GRACE_PERIOD: u64 = 14 * 86400;
+
+// Runs the same checks as the setters so a bad payload is rejected before the timelock starts
+fn validate_config_change(deps: Deps, change: &ConfigChange) -> Result<(), ContractError> {
+    match change {
+        ConfigChange::SetFeeDestination { fee_destination } => {
+            deps.api.addr_validate(fee_destination.as_str())?;
+        }
+        ConfigChange::SetProtocolBuyFeePercent {
+            protocol_buy_fee_percent: fee_percent,
+        }
+        | ConfigChange::SetProtocolSellFeePercent {
+            protocol_sell_fee_percent: fee_percent,
+        } => validate_protocol_fee_percent(*fee_percent)?,
+        ConfigChange::SetSubjectBuyFeePercent {
+            subject_buy_fee_percent: fee_percent,
+        }
+        | ConfigChange::SetSubjectSellFeePercent {
+            subject_sell_fee_percent: fee_percent,
+        } => validate_subject_fee_percent(*fee_percent)?,
+        ConfigChange::SetReferralBuyFeePercent {
+            referral_buy_fee_percent: fee_percent,
+        }
+        | ConfigChange::SetReferralSellFeePercent {
+            referral_sell_fee_percent: fee_percent,
+        } => validate_referral_fee_percent(*fee_percent)?,
+        ConfigChange::SetReferralTiers { tiers } => validate_referral_tiers(tiers)?,
+        ConfigChange::SetSubjectFeeBounds {
+            min_fee_percent,
+            max_fee_percent,
+        } => validate_subject_fee_bounds(*min_fee_percent, *max_fee_percent)?,
+        ConfigChange::SetProtocolFeeRecipients { recipients } => {
+            validate_protocol_fee_recipients(deps.api, recipients.clone())?;
+        }
+        ConfigChange::SetConfigChangeDelay { delay } => validate_config_change_delay(*delay)?,
+        ConfigChange::SetTradeLimits { .. } => {}
+        ConfigChange::SetCircuitBreaker {
+            max_price_move_percent,
+            window_blocks,
+            cooldown_blocks,
+        } => validate_circuit_breaker(*max_price_move_percent, *window_blocks, *cooldown_blocks)?,
+        ConfigChange::SetScreeningContract { contract } => {
+            if let Some(contract) = contract {
+                deps.api.addr_validate(contract.as_str())?;
+            }
+        }
+        ConfigChange::SetMySubjectFee { buy, sell } => {
+            validate_my_subject_fee(deps.storage, *buy, *sell)?
+        }
+        ConfigChange::SetHolderRewardPercent { percent } => {
+            validate_holder_reward_percent(*percent)?
+        }
+    }
+    Ok(())
+}
+
+// Role that may propose, execute and cancel a change. Only fees stay with the FeeManager,
+// subject settings need no role and belong to the subject that proposed them.
+fn get_config_change_role(change: &ConfigChange) -> Option<Role> {
+    match change {
+        ConfigChange::SetMySubjectFee { .. } | ConfigChange::SetHolderRewardPercent { .. } => None,
+        ConfigChange::SetConfigChangeDelay { .. }
+        | ConfigChange::SetTradeLimits { .. }
+        | ConfigChange::SetCircuitBreaker { .. }
+        | ConfigChange::SetScreeningContract { .. } => Some(Role::Admin),
+        _ => Some(Role::FeeManager),
+    }
+}
+
+fn ensure_config_change_role(
+    storage: &dyn Storage,
+    change: &ConfigChange,
+    sender: &Addr,
+    proposer: &Addr,
+) -> Result<(), ContractError> {
+    match get_config_change_role(change) {
+        Some(role) => ensure_role(storage, role, sender),
+        None if sender == proposer => Ok(()),
+        None => Err(ContractError::Unauthorized {}),
+    }
+}
+
+pub fn propose_config_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    change: ConfigChange,
+) -> Result<Response, ContractError> {
+    ensure_config_change_role(deps.storage, &change, &info.sender, &info.sender)?;
+    validate_config_change(deps.as_ref(), &change)?;
+    let id = CONFIG_CHANGE_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    let eta = env.block.time.plus_seconds(get_config_change_delay(deps.storage)?);
+    CONFIG_CHANGE_COUNT.save(deps.storage, &id)?;
+    PENDING_CONFIG_CHANGES.save(
+        deps.storage,
+        id,
+        &PendingConfigChange {
+            id,
+            change,
+            proposer: info.sender.clone(),
+            eta,
+        },
+    )?;
+    Ok(Response::new().add_event(
+        Event::new("propose_config_change")
+            .add_attribute("id", id.to_string())
+            .add_attribute("proposer", info.sender)
+            .add_attribute("eta", eta.seconds().to_string()),
+    ))
+}
+
+pub fn execute_config_change(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_CONFIG_CHANGES
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::ConfigChangeNotFound {})?;
+    ensure_config_change_role(deps.storage, &pending.change, &info.sender, &pending.proposer)?;
+    if env.block.time < pending.eta {
+        return Err(ContractError::ConfigChangeNotReady {
+            eta: pending.eta.seconds().to_string(),
+        });
+    }
+    let expires_at = pending.eta.plus_seconds(CONFIG_CHANGE_GRACE_PERIOD);
+    if env.block.time > expires_at {
+        return Err(ContractError::ConfigChangeExpired {
+            expires_at: expires_at.seconds().to_string(),
+        });
+    }
+    PENDING_CONFIG_CHANGES.remove(deps.storage, id);
+
+    let response = match pending.change {
+        ConfigChange::SetFeeDestination { fee_destination } => {
+            set_fee_destination(deps, info, fee_destination)
+        }
+        ConfigChange::SetProtocolBuyFeePercent {
+            protocol_buy_fee_percent,
+        } => set_protocol_buy_fee_percent(deps, info, protocol_buy_fee_percent),
+        ConfigChange::SetProtocolSellFeePercent {
+            protocol_sell_fee_percent,
+        } => set_protocol_sell_fee_percent(deps, info, protocol_sell_fee_percent),
+        ConfigChange::SetSubjectBuyFeePercent {
+            subject_buy_fee_percent,
+        } => set_subject_buy_fee_percent(deps, info, subject_buy_fee_percent),
+        ConfigChange::SetSubjectSellFeePercent {
+            subject_sell_fee_percent,
+        } => set_subject_sell_fee_percent(deps, info, subject_sell_fee_percent),
+        ConfigChange::SetReferralBuyFeePercent {
+            referral_buy_fee_percent,
+        } => set_referral_buy_fee_percent(deps, info, referral_buy_fee_percent),
+        ConfigChange::SetReferralSellFeePercent {
+            referral_sell_fee_percent,
+        } => set_referral_sell_fee_percent(deps, info, referral_sell_fee_percent),
+        ConfigChange::SetReferralTiers { tiers } => set_referral_tiers(deps, info, tiers),
+        ConfigChange::SetSubjectFeeBounds {
+            min_fee_percent,
+            max_fee_percent,
+        } => set_subject_fee_bounds(deps, info, min_fee_percent, max_fee_percent),
+        ConfigChange::SetProtocolFeeRecipients { recipients } => {
+            set_protocol_fee_recipients(deps, info, recipients)
+        }
+        ConfigChange::SetConfigChangeDelay { delay } => set_config_change_delay(deps, delay),
+        ConfigChange::SetTradeLimits {
+            buy_sell_cooldown_blocks,
+            max_trades_per_block,
+        } => set_trade_limits(deps, info, buy_sell_cooldown_blocks, max_trades_per_block),
+        ConfigChange::SetCircuitBreaker {
+            max_price_move_percent,
+            window_blocks,
+            cooldown_blocks,
+        } => set_circuit_breaker(
+            deps,
+            info,
+            max_price_move_percent,
+            window_blocks,
+            cooldown_blocks,
+        ),
+        ConfigChange::SetScreeningContract { contract } => {
+            set_screening_contract(deps, info, contract)
+        }
+        ConfigChange::SetMySubjectFee { buy, sell } => set_my_subject_fee(deps, info, buy, sell),
+        ConfigChange::SetHolderRewardPercent { percent } => {
+            set_holder_reward_percent(deps, info, percent)
+        }
+    }?;
+    Ok(response.add_event(Event::new("execute_config_change").add_attribute("id", id.to_string())))
+}
+
+pub fn cancel_config_change(
+    deps: DepsMut,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let pending = PENDING_CONFIG_CHANGES
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::ConfigChangeNotFound {})?;
+    ensure_config_change_role(deps.storage, &pending.change, &info.sender, &pending.proposer)?;
+    PENDING_CONFIG_CHANGES.remove(deps.storage, id);
+    Ok(Response::new().add_event(
+        Event::new("cancel_config_change")
+            .add_attribute("id", id.to_string())
+            .add_attribute("sender", info.sender),
+    ))
+}
+
+fn validate_config_change_delay(delay: u64) -> Result<(), ContractError> {
+    if delay > MAX_CONFIG_CHANGE_DELAY {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot set delay higher than MAX_CONFIG_CHANGE_DELAY",
+        )));
+    }
+    Ok(())
+}
+
+fn set_config_change_delay(deps: DepsMut, delay: u64) -> Result<Response, ContractError> {
+    validate_config_change_delay(delay)?;
+    CONFIG_CHANGE_DELAY.save(deps.storage, &delay)?;
+    Ok(Response::new()
+        .add_event(Event::new("set_config_change_delay").add_attribute("delay", delay.to_string())))
+}
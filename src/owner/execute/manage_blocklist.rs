@@ -0,0 +1,51 @@
+use cosmwasm_std::{Addr, DepsMut, Event, MessageInfo, Response};
+
+use crate::{
+    state::{Role, BLOCKED, SCREENING_CONTRACT},
+    util::ensure_role,
+    ContractError,
+};
+
+pub fn update_blocklist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<Addr>,
+    remove: Vec<Addr>,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Admin, &info.sender)?;
+    for address in &add {
+        let validated_address = deps.api.addr_validate(address.as_str())?;
+        BLOCKED.save(deps.storage, &validated_address, &true)?;
+    }
+    for address in &remove {
+        let validated_address = deps.api.addr_validate(address.as_str())?;
+        BLOCKED.remove(deps.storage, &validated_address);
+    }
+    Ok(Response::new().add_event(
+        Event::new("update_blocklist")
+            .add_attribute("added", add.len().to_string())
+            .add_attribute("removed", remove.len().to_string())
+            .add_attribute("sender", info.sender),
+    ))
+}
+
+pub fn set_screening_contract(
+    deps: DepsMut,
+    info: MessageInfo,
+    contract: Option<Addr>,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Admin, &info.sender)?;
+    let event = Event::new("set_screening_contract").add_attribute("sender", info.sender);
+    let event = match contract {
+        Some(contract) => {
+            let validated_contract_address = deps.api.addr_validate(contract.as_str())?;
+            SCREENING_CONTRACT.save(deps.storage, &validated_contract_address)?;
+            event.add_attribute("contract", validated_contract_address)
+        }
+        None => {
+            SCREENING_CONTRACT.remove(deps.storage);
+            event.add_attribute("contract", "none")
+        }
+    };
+    Ok(Response::new().add_event(event))
+}
@@ -0,0 +1,85 @@
+use cosmwasm_std::{
+    Addr, DepsMut, Event, MessageInfo, Order, Response, StdError, StdResult, Storage,
+};
+
+use crate::{
+    state::{Role, ROLES},
+    util::{ensure_role, has_role},
+    ContractError,
+};
+
+pub fn grant_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    account: Addr,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Admin, &info.sender)?;
+    let validated_account = deps.api.addr_validate(account.as_str())?;
+    if has_role(deps.storage, role, &validated_account)? {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Account already has this role",
+        )));
+    }
+    ROLES.save(deps.storage, (role.as_str(), &validated_account), &true)?;
+    Ok(Response::new().add_event(
+        Event::new("grant_role")
+            .add_attribute("role", role.as_str())
+            .add_attribute("account", validated_account)
+            .add_attribute("sender", info.sender),
+    ))
+}
+
+pub fn revoke_role(
+    deps: DepsMut,
+    info: MessageInfo,
+    role: Role,
+    account: Addr,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Admin, &info.sender)?;
+    let validated_account = deps.api.addr_validate(account.as_str())?;
+    if !has_role(deps.storage, role, &validated_account)? {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Account does not have this role",
+        )));
+    }
+    ensure_not_last_admin(deps.storage, role, &validated_account)?;
+    ROLES.remove(deps.storage, (role.as_str(), &validated_account));
+    Ok(Response::new().add_event(
+        Event::new("revoke_role")
+            .add_attribute("role", role.as_str())
+            .add_attribute("account", validated_account)
+            .add_attribute("sender", info.sender),
+    ))
+}
+
+pub fn renounce_role(deps: DepsMut, info: MessageInfo, role: Role) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, role, &info.sender)?;
+    ensure_not_last_admin(deps.storage, role, &info.sender)?;
+    ROLES.remove(deps.storage, (role.as_str(), &info.sender));
+    Ok(Response::new().add_event(
+        Event::new("renounce_role")
+            .add_attribute("role", role.as_str())
+            .add_attribute("account", info.sender),
+    ))
+}
+
+// The contract must always keep an admin, otherwise no role could ever be granted again
+fn ensure_not_last_admin(
+    storage: &dyn Storage,
+    role: Role,
+    account: &Addr,
+) -> Result<(), ContractError> {
+    if role != Role::Admin {
+        return Ok(());
+    }
+    let admins = ROLES
+        .prefix(Role::Admin.as_str())
+        .keys(storage, None, None, Order::Ascending)
+        .take(2)
+        .collect::<StdResult<Vec<Addr>>>()?;
+    if admins.iter().all(|admin| admin == account) {
+        return Err(ContractError::Std(StdError::generic_err("Cannot remove the last admin")));
+    }
+    Ok(())
+}
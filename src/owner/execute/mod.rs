@@ -4,14 +4,60 @@ pub use set_fee_destination::set_fee_destination;
 mod set_protocol_fee_percent;
 pub use set_protocol_fee_percent::set_protocol_buy_fee_percent;
 pub use set_protocol_fee_percent::set_protocol_sell_fee_percent;
+pub use set_protocol_fee_percent::validate_protocol_fee_percent;
 
 mod set_subject_fee_percent;
 pub use set_subject_fee_percent::set_subject_buy_fee_percent;
 pub use set_subject_fee_percent::set_subject_sell_fee_percent;
+pub use set_subject_fee_percent::validate_subject_fee_percent;
 
 mod set_referral_fee_percent;
 pub use set_referral_fee_percent::set_referral_buy_fee_percent;
 pub use set_referral_fee_percent::set_referral_sell_fee_percent;
+pub use set_referral_fee_percent::validate_referral_fee_percent;
 
-mod toggle_trading;
-pub use toggle_trading::toggle_trading;
\ No newline at end of file
+mod set_trading_mode;
+pub use set_trading_mode::set_trading_mode;
+
+mod set_referral_tiers;
+pub use set_referral_tiers::set_referral_tiers;
+pub use set_referral_tiers::validate_referral_tiers;
+
+mod set_subject_fee_bounds;
+pub use set_subject_fee_bounds::set_subject_fee_bounds;
+pub use set_subject_fee_bounds::validate_subject_fee_bounds;
+
+mod set_protocol_fee_recipients;
+pub use set_protocol_fee_recipients::set_protocol_fee_recipients;
+pub use set_protocol_fee_recipients::validate_protocol_fee_recipients;
+
+mod manage_roles;
+pub use manage_roles::grant_role;
+pub use manage_roles::renounce_role;
+pub use manage_roles::revoke_role;
+
+mod config_change;
+pub use config_change::cancel_config_change;
+pub use config_change::execute_config_change;
+pub use config_change::propose_config_change;
+
+mod set_market_status;
+pub use set_market_status::set_market_status;
+
+mod sweep_surplus;
+pub use sweep_surplus::sweep_surplus;
+
+mod set_transfers_enabled;
+pub use set_transfers_enabled::set_transfers_enabled;
+
+mod set_trade_limits;
+pub use set_trade_limits::set_trade_limits;
+
+mod circuit_breaker;
+pub use circuit_breaker::reset_circuit_breaker;
+pub use circuit_breaker::set_circuit_breaker;
+pub use circuit_breaker::validate_circuit_breaker;
+
+mod manage_blocklist;
+pub use manage_blocklist::set_screening_contract;
+pub use manage_blocklist::update_blocklist;
\ No newline at end of file
@@ -1,6 +1,10 @@
 use cosmwasm_std::{Addr, DepsMut, Event, MessageInfo, Response};
 
-use crate::{state::STATE, ContractError};
+use crate::{
+    state::{FeeRecipient, Role, STATE},
+    util::{ensure_not_blocked, ensure_role, TOTAL_FEE_WEIGHT},
+    ContractError,
+};
 
 pub fn set_fee_destination(
     deps: DepsMut,
@@ -8,12 +12,14 @@ pub fn set_fee_destination(
     fee_destination: Addr,
 ) -> Result<Response, ContractError> {
     let validated_address = deps.api.addr_validate(&fee_destination.to_string())?;
+    ensure_not_blocked(deps.as_ref(), &[&validated_address])?;
     let destination = validated_address.to_string();
+    ensure_role(deps.storage, Role::FeeManager, &info.sender)?;
     STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
-        if info.sender != state.owner {
-            return Err(ContractError::Unauthorized {});
-        }
-        state.protocol_fee_destination = validated_address;
+        state.protocol_fee_recipients = vec![FeeRecipient {
+            address: validated_address,
+            weight: TOTAL_FEE_WEIGHT,
+        }];
         Ok(state)
     })?;
     Ok(Response::new()
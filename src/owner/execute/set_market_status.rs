@@ -0,0 +1,30 @@
+use cosmwasm_std::{Addr, DepsMut, Event, MessageInfo, Response};
+
+use crate::{
+    state::{MarketStatus, Role, MARKET_STATUS},
+    util::{ensure_role, get_market_status, has_role},
+    ContractError,
+};
+
+pub fn set_market_status(
+    deps: DepsMut,
+    info: MessageInfo,
+    shares_subject: Addr,
+    status: MarketStatus,
+) -> Result<Response, ContractError> {
+    if !has_role(deps.storage, Role::Admin, &info.sender)? {
+        ensure_role(deps.storage, Role::Moderator, &info.sender)?;
+    }
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    // Delisting is final, holders can only exit
+    if get_market_status(deps.storage, &validated_shares_subject_address)? == MarketStatus::Delisted {
+        return Err(ContractError::MarketDelisted {});
+    }
+    MARKET_STATUS.save(deps.storage, &validated_shares_subject_address, &status)?;
+    Ok(Response::new().add_event(
+        Event::new("set_market_status")
+            .add_attribute("shares_subject", validated_shares_subject_address)
+            .add_attribute("status", status.as_str())
+            .add_attribute("sender", info.sender),
+    ))
+}
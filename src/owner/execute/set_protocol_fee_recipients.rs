@@ -0,0 +1,68 @@
+use cosmwasm_std::{Addr, Api, DepsMut, Event, MessageInfo, Response, StdError};
+
+use crate::{
+    state::{FeeRecipient, Role, STATE},
+    util::{ensure_not_blocked, ensure_role, TOTAL_FEE_WEIGHT},
+    ContractError,
+};
+
+pub fn validate_protocol_fee_recipients(
+    api: &dyn Api,
+    recipients: Vec<FeeRecipient>,
+) -> Result<Vec<FeeRecipient>, ContractError> {
+    if recipients.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "At least one protocol fee recipient is required",
+        )));
+    }
+    let mut validated_recipients: Vec<FeeRecipient> = Vec::with_capacity(recipients.len());
+    let mut total_weight: u64 = 0;
+    for recipient in recipients {
+        let validated_address = api.addr_validate(recipient.address.as_str())?;
+        if recipient.weight == 0 {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Protocol fee recipient weight must be positive",
+            )));
+        }
+        if validated_recipients.iter().any(|r| r.address == validated_address) {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Duplicate protocol fee recipient",
+            )));
+        }
+        total_weight = total_weight.checked_add(recipient.weight).ok_or_else(|| {
+            ContractError::Std(StdError::generic_err(
+                "Protocol fee recipient weights must sum to 10000",
+            ))
+        })?;
+        validated_recipients.push(FeeRecipient {
+            address: validated_address,
+            weight: recipient.weight,
+        });
+    }
+    if total_weight != TOTAL_FEE_WEIGHT {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Protocol fee recipient weights must sum to 10000",
+        )));
+    }
+    Ok(validated_recipients)
+}
+
+pub fn set_protocol_fee_recipients(
+    deps: DepsMut,
+    info: MessageInfo,
+    recipients: Vec<FeeRecipient>,
+) -> Result<Response, ContractError> {
+    let validated_recipients = validate_protocol_fee_recipients(deps.api, recipients)?;
+    let addresses: Vec<&Addr> = validated_recipients.iter().map(|r| &r.address).collect();
+    ensure_not_blocked(deps.as_ref(), &addresses)?;
+    let mut event = Event::new("set_protocol_fee_recipients");
+    for recipient in validated_recipients.iter() {
+        event = event.add_attribute(recipient.address.to_string(), recipient.weight.to_string());
+    }
+    ensure_role(deps.storage, Role::FeeManager, &info.sender)?;
+    STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
+        state.protocol_fee_recipients = validated_recipients;
+        Ok(state)
+    })?;
+    Ok(Response::new().add_event(event))
+}
@@ -0,0 +1,38 @@
+use cosmwasm_std::{DepsMut, Event, MessageInfo, Response, StdError, Uint128};
+
+use crate::{
+    state::{ReferralTier, Role, REFERRAL_TIERS},
+    util::ensure_role,
+    ContractError,
+};
+
+const MAX_FEE_PERCENT: Uint128 = Uint128::new(2500);
+
+pub fn validate_referral_tiers(tiers: &[ReferralTier]) -> Result<(), ContractError> {
+    for (index, tier) in tiers.iter().enumerate() {
+        if tier.buy_fee_percent > MAX_FEE_PERCENT || tier.sell_fee_percent > MAX_FEE_PERCENT {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Cannot set fees higher than MAX_FEE_PERCENT",
+            )));
+        }
+        if index > 0 && tier.min_volume <= tiers[index - 1].min_volume {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Referral tiers must be sorted by ascending min_volume",
+            )));
+        }
+    }
+    Ok(())
+}
+
+pub fn set_referral_tiers(
+    deps: DepsMut,
+    info: MessageInfo,
+    tiers: Vec<ReferralTier>,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::FeeManager, &info.sender)?;
+    validate_referral_tiers(&tiers)?;
+    REFERRAL_TIERS.save(deps.storage, &tiers)?;
+    Ok(Response::new().add_event(
+        Event::new("set_referral_tiers").add_attribute("tiers", tiers.len().to_string()),
+    ))
+}
@@ -0,0 +1,46 @@
+use cosmwasm_std::{DepsMut, Event, MessageInfo, Response, StdError, Uint128};
+
+use crate::{
+    state::{Role, SubjectFeeBounds, SUBJECT_FEE_BOUNDS},
+    util::{ensure_role, MAX_SUBJECT_FEE_PERCENT},
+    ContractError,
+};
+
+pub fn validate_subject_fee_bounds(
+    min_fee_percent: Uint128,
+    max_fee_percent: Uint128,
+) -> Result<(), ContractError> {
+    if max_fee_percent > MAX_SUBJECT_FEE_PERCENT {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot set fees higher than MAX_FEE_PERCENT",
+        )));
+    }
+    if min_fee_percent > max_fee_percent {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Minimum fee cannot be higher than maximum fee",
+        )));
+    }
+    Ok(())
+}
+
+pub fn set_subject_fee_bounds(
+    deps: DepsMut,
+    info: MessageInfo,
+    min_fee_percent: Uint128,
+    max_fee_percent: Uint128,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::FeeManager, &info.sender)?;
+    validate_subject_fee_bounds(min_fee_percent, max_fee_percent)?;
+    SUBJECT_FEE_BOUNDS.save(
+        deps.storage,
+        &SubjectFeeBounds {
+            min_fee_percent,
+            max_fee_percent,
+        },
+    )?;
+    Ok(Response::new().add_event(
+        Event::new("set_subject_fee_bounds")
+            .add_attribute("min_fee_percent", min_fee_percent)
+            .add_attribute("max_fee_percent", max_fee_percent),
+    ))
+}
@@ -0,0 +1,28 @@
+use cosmwasm_std::{DepsMut, Event, MessageInfo, Response};
+
+use crate::{
+    state::{Role, TradeLimits, TRADE_LIMITS},
+    util::ensure_role,
+    ContractError,
+};
+
+pub fn set_trade_limits(
+    deps: DepsMut,
+    info: MessageInfo,
+    buy_sell_cooldown_blocks: u64,
+    max_trades_per_block: u32,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Admin, &info.sender)?;
+    TRADE_LIMITS.save(
+        deps.storage,
+        &TradeLimits {
+            buy_sell_cooldown_blocks,
+            max_trades_per_block,
+        },
+    )?;
+    Ok(Response::new().add_event(
+        Event::new("set_trade_limits")
+            .add_attribute("buy_sell_cooldown_blocks", buy_sell_cooldown_blocks.to_string())
+            .add_attribute("max_trades_per_block", max_trades_per_block.to_string()),
+    ))
+}
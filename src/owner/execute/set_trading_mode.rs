@@ -0,0 +1,31 @@
+use cosmwasm_std::{DepsMut, Event, MessageInfo, Response};
+
+use crate::{
+    state::{Role, State, TradingMode, STATE},
+    util::ensure_role,
+    ContractError,
+};
+
+pub fn set_trading_mode(
+    deps: DepsMut,
+    info: MessageInfo,
+    mode: TradingMode,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Pauser, &info.sender)?;
+    let state: State = STATE.load(deps.storage)?;
+
+    if state.trading_mode == mode {
+        return Err(ContractError::TradingStateTheSame {});
+    }
+
+    STATE.update(deps.storage, |mut state| -> Result<_, ContractError> {
+        state.trading_mode = mode;
+        Ok(state)
+    })?;
+    Ok(Response::new().add_event(
+        Event::new("set_trading_mode")
+            .add_attribute("previous_mode", state.trading_mode.as_str())
+            .add_attribute("mode", mode.as_str())
+            .add_attribute("sender", info.sender),
+    ))
+}
@@ -0,0 +1,29 @@
+use cosmwasm_std::{Addr, DepsMut, Event, MessageInfo, Response};
+
+use crate::{
+    state::{Role, TRANSFERS_DISABLED},
+    util::ensure_role,
+    ContractError,
+};
+
+// Owner switch for subjects that opt out of transfers, only the admin flips it
+pub fn set_transfers_enabled(
+    deps: DepsMut,
+    info: MessageInfo,
+    shares_subject: Addr,
+    enabled: bool,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Admin, &info.sender)?;
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    if enabled {
+        TRANSFERS_DISABLED.remove(deps.storage, &validated_shares_subject_address);
+    } else {
+        TRANSFERS_DISABLED.save(deps.storage, &validated_shares_subject_address, &true)?;
+    }
+    Ok(Response::new().add_event(
+        Event::new("set_transfers_enabled")
+            .add_attribute("shares_subject", validated_shares_subject_address)
+            .add_attribute("enabled", enabled.to_string())
+            .add_attribute("sender", info.sender),
+    ))
+}
@@ -0,0 +1,43 @@
+use cosmwasm_std::{coins, Addr, BankMsg, DepsMut, Env, Event, MessageInfo, Response};
+
+use crate::{
+    state::Role,
+    util::{ensure_reserves_migrated, ensure_role, get_required_balance},
+    ContractError,
+};
+
+pub fn sweep_surplus(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    denom: String,
+    recipient: Addr,
+) -> Result<Response, ContractError> {
+    ensure_role(deps.storage, Role::Admin, &info.sender)?;
+    let validated_recipient_address = deps.api.addr_validate(recipient.as_str())?;
+    ensure_reserves_migrated(deps.storage)?;
+
+    // Only what is above the funds backing outstanding shares can leave
+    let balance = deps
+        .querier
+        .query_balance(&env.contract.address, &denom)?
+        .amount;
+    let required = get_required_balance(deps.storage, &denom)?;
+    let surplus = balance.saturating_sub(required);
+    if surplus.is_zero() {
+        return Err(ContractError::NoSurplus {});
+    }
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("sweep_surplus")
+                .add_attribute("denom", &denom)
+                .add_attribute("recipient", &validated_recipient_address)
+                .add_attribute("amount", surplus)
+                .add_attribute("required", required),
+        )
+        .add_message(BankMsg::Send {
+            to_address: validated_recipient_address.to_string(),
+            amount: coins(surplus.u128(), denom),
+        }))
+}
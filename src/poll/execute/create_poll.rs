@@ -0,0 +1,63 @@
+use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, Response, StdError, Timestamp, Uint128};
+
+use crate::{
+    state::{Poll, POLLS, POLL_COUNT},
+    ContractError,
+};
+
+const MAX_POLL_OPTIONS: usize = 10;
+const MAX_POLL_TEXT_LENGTH: usize = 280;
+
+// Subjects poll the holders of their own market, voting power is fixed at creation
+pub fn create_poll(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    question: String,
+    options: Vec<String>,
+    end_time: Timestamp,
+) -> Result<Response, ContractError> {
+    if options.len() < 2 || options.len() > MAX_POLL_OPTIONS {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Polls need between 2 and {} options",
+            MAX_POLL_OPTIONS
+        ))));
+    }
+    let invalid_text = std::iter::once(&question)
+        .chain(options.iter())
+        .any(|text| text.trim().is_empty() || text.len() > MAX_POLL_TEXT_LENGTH);
+    if invalid_text {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Poll question and options must be 1 to {} characters",
+            MAX_POLL_TEXT_LENGTH
+        ))));
+    }
+    if end_time <= env.block.time {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Poll end time must be in the future",
+        )));
+    }
+
+    let id = POLL_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    POLL_COUNT.save(deps.storage, &id)?;
+    POLLS.save(
+        deps.storage,
+        id,
+        &Poll {
+            shares_subject: info.sender.clone(),
+            question,
+            tally: vec![Uint128::zero(); options.len()],
+            options,
+            start_height: env.block.height,
+            end_time,
+        },
+    )?;
+
+    Ok(Response::new().add_event(
+        Event::new("create_poll")
+            .add_attribute("id", id.to_string())
+            .add_attribute("shares_subject", info.sender)
+            .add_attribute("start_height", env.block.height.to_string())
+            .add_attribute("end_time", end_time.to_string()),
+    ))
+}
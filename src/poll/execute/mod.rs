@@ -0,0 +1,5 @@
+mod create_poll;
+pub use create_poll::create_poll;
+
+mod vote;
+pub use vote::vote;
@@ -0,0 +1,49 @@
+use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, Response};
+
+use crate::{
+    state::{PollVote, POLLS, POLL_VOTES, SHARES_BALANCE},
+    ContractError,
+};
+
+pub fn vote(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    poll_id: u64,
+    option: u32,
+) -> Result<Response, ContractError> {
+    let mut poll = POLLS
+        .may_load(deps.storage, poll_id)?
+        .ok_or(ContractError::PollNotFound {})?;
+    if env.block.time >= poll.end_time {
+        return Err(ContractError::PollClosed {});
+    }
+    if POLL_VOTES.has(deps.storage, (poll_id, &info.sender)) {
+        return Err(ContractError::AlreadyVoted {});
+    }
+    if option as usize >= poll.options.len() {
+        return Err(ContractError::InvalidPollOption {});
+    }
+    // Shares bought after the poll started do not count
+    let weight = SHARES_BALANCE
+        .may_load_at_height(
+            deps.storage,
+            (&info.sender, &poll.shares_subject),
+            poll.start_height,
+        )?
+        .unwrap_or_default();
+    if weight.is_zero() {
+        return Err(ContractError::NoVotingPower {});
+    }
+    poll.tally[option as usize] += weight;
+    POLLS.save(deps.storage, poll_id, &poll)?;
+    POLL_VOTES.save(deps.storage, (poll_id, &info.sender), &PollVote { option, weight })?;
+
+    Ok(Response::new().add_event(
+        Event::new("vote")
+            .add_attribute("poll_id", poll_id.to_string())
+            .add_attribute("voter", info.sender)
+            .add_attribute("option", option.to_string())
+            .add_attribute("weight", weight),
+    ))
+}
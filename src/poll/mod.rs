@@ -0,0 +1,2 @@
+pub mod execute;
+pub mod query;
\ No newline at end of file
@@ -0,0 +1,24 @@
+use cosmwasm_std::{Addr, Deps, Env, StdError, StdResult};
+
+use crate::{
+    msg::{PollResponse, PollStatus, PollVoteResponse},
+    state::{POLLS, POLL_VOTES},
+};
+
+pub fn get_poll(deps: Deps, env: Env, id: u64) -> StdResult<PollResponse> {
+    let poll = POLLS
+        .may_load(deps.storage, id)?
+        .ok_or_else(|| StdError::generic_err("Poll not found"))?;
+    let status = if env.block.time < poll.end_time {
+        PollStatus::Open
+    } else {
+        PollStatus::Closed
+    };
+    Ok(PollResponse { id, poll, status })
+}
+
+pub fn get_poll_vote(deps: Deps, id: u64, voter: Addr) -> StdResult<PollVoteResponse> {
+    let validated_voter_address = deps.api.addr_validate(voter.as_str())?;
+    let vote = POLL_VOTES.may_load(deps.storage, (id, &validated_voter_address))?;
+    Ok(PollVoteResponse { vote })
+}
@@ -0,0 +1,2 @@
+mod get_poll;
+pub use get_poll::{get_poll, get_poll_vote};
@@ -1,10 +1,15 @@
-use cosmwasm_std::{Addr, Uint128};
-use cw_storage_plus::{Item, Map};
+use cosmwasm_std::{Addr, Decimal256, Timestamp, Uint128};
+use cw_storage_plus::{Item, Map, SnapshotMap, Strategy};
+use cw_utils::{Duration, Expiration};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
+use crate::msg::ConfigChange;
+
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
 pub struct State {
+    // Informational only, authorization goes through ROLES. Pre-role contracts migrate their
+    // owner into every role, see migrate_roles.
     pub owner: Addr,
     pub subject_buy_fee_percent: Uint128,
     pub subject_sell_fee_percent: Uint128,
@@ -12,11 +17,312 @@ pub struct State {
     pub protocol_sell_fee_percent: Uint128,
     pub referral_buy_fee_percent: Uint128,
     pub referral_sell_fee_percent: Uint128,
-    pub protocol_fee_destination: Addr,
-    pub trading_is_enabled: bool,
+    pub protocol_fee_recipients: Vec<FeeRecipient>,
+    pub trading_mode: TradingMode,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum TradingMode {
+    Open,
+    // Emergency mode, holders can still exit
+    SellOnly,
+    Halted,
+}
+
+impl TradingMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradingMode::Open => "open",
+            TradingMode::SellOnly => "sell_only",
+            TradingMode::Halted => "halted",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct FeeRecipient {
+    pub address: Addr,
+    // Basis points, the weights of all recipients sum to 10_000
+    pub weight: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ReferralTier {
+    pub min_volume: Uint128,
+    pub buy_fee_percent: Uint128,
+    pub sell_fee_percent: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SubjectFee {
+    pub buy_fee_percent: Uint128,
+    pub sell_fee_percent: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SubjectFeeBounds {
+    pub min_fee_percent: Uint128,
+    pub max_fee_percent: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,
+    Pauser,
+    FeeManager,
+    Moderator,
+}
+
+impl Role {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Role::Admin => "admin",
+            Role::Pauser => "pauser",
+            Role::FeeManager => "fee_manager",
+            Role::Moderator => "moderator",
+        }
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, JsonSchema, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum MarketStatus {
+    #[default]
+    Active,
+    // Sells only, can be lifted again
+    BuyPaused,
+    // No trading at all
+    Paused,
+    // Permanent sell-only wind-down
+    Delisted,
+}
+
+impl MarketStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MarketStatus::Active => "active",
+            MarketStatus::BuyPaused => "buy_paused",
+            MarketStatus::Paused => "paused",
+            MarketStatus::Delisted => "delisted",
+        }
+    }
+
+    pub fn can_buy(&self) -> bool {
+        *self == MarketStatus::Active
+    }
+
+    pub fn can_sell(&self) -> bool {
+        *self != MarketStatus::Paused
+    }
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ShareAllowance {
+    pub allowance: Uint128,
+    pub expires: Expiration,
+}
+
+// Anti-bot rules for the first buys of a market, `ends` is set by the first buy
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct LaunchProtection {
+    pub window: Duration,
+    pub max_shares_per_address: Option<Uint128>,
+    pub min_buy_interval: Option<Duration>,
+    pub allowlist_only: bool,
+    pub ends: Option<Expiration>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct LaunchPurchases {
+    pub count: Uint128,
+    pub next_buy: Option<Expiration>,
+}
+
+// Zero disables a limit
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct TradeLimits {
+    pub buy_sell_cooldown_blocks: u64,
+    pub max_trades_per_block: u32,
+}
+
+// Progress of the reserve rebuild in migrate, kept until every subject is done.
+// `shares_subject` is the subject being summed, `supply_summed` the shares of it counted so far.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct ReserveMigration {
+    pub shares_subject: Option<Addr>,
+    pub supply_summed: Uint128,
+    pub reserve: Uint128,
+    pub total_reserve: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct LastTrade {
+    pub last_buy_height: Option<u64>,
+    pub last_sell_height: Option<u64>,
+    pub block_height: u64,
+    pub block_trades: u32,
+}
+
+// Same percent scale as the fees, zero disables the breaker
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct CircuitBreakerConfig {
+    pub max_price_move_percent: Uint128,
+    pub window_blocks: u64,
+    pub cooldown_blocks: u64,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct CircuitBreaker {
+    pub reference_price: Uint128,
+    pub window_start: u64,
+    pub halted_until: Option<u64>,
+}
+
+// Holders claim `amount * balance / supply` with their balance at `snapshot_height`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Airdrop {
+    pub shares_subject: Addr,
+    pub denom: String,
+    pub amount: Uint128,
+    pub claimed: Uint128,
+    pub snapshot_height: u64,
+    pub supply: Uint128,
+    pub expires: Expiration,
+    pub reclaimed: bool,
+}
+
+// `weight` is the amount boosted by the lock duration, see get_lock_weight
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct ShareLock {
+    pub shares_subject: Addr,
+    pub amount: Uint128,
+    pub weight: Uint128,
+    pub unlocks_at: Timestamp,
+}
+
+// Released linearly from `start` over `duration` seconds, nothing before `cliff` seconds.
+// A cliff equal to the duration releases everything at once.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct SelfVesting {
+    pub amount: Uint128,
+    pub start: Timestamp,
+    pub cliff: u64,
+    pub duration: u64,
+}
+
+// Votes weigh the voter's balance at the start of `start_height`, `tally` follows `options`
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct Poll {
+    pub shares_subject: Addr,
+    pub question: String,
+    pub options: Vec<String>,
+    pub tally: Vec<Uint128>,
+    pub start_height: u64,
+    pub end_time: Timestamp,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PollVote {
+    pub option: u32,
+    pub weight: Uint128,
+}
+
+// `index` is the subject reward index the `pending` amount was last settled at
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema, Default)]
+pub struct HolderRewards {
+    pub index: Decimal256,
+    pub pending: Uint128,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+pub struct PendingConfigChange {
+    pub id: u64,
+    pub change: ConfigChange,
+    pub proposer: Addr,
+    pub eta: Timestamp,
 }
 
 pub const STATE: Item<State> = Item::new("state");
-pub const SHARES_SUPPLY: Map<&Addr, Uint128> = Map::new("shares_supply");
-pub const SHARES_BALANCE: Map<(&Addr, &Addr), Uint128> = Map::new("shares_balance");
-pub const SHARES_HOLDERS: Map<&Addr, Uint128> = Map::new("shares_holders");
\ No newline at end of file
+// Snapshotted every block, a value at some height is the value at the start of that block
+pub const SHARES_SUPPLY: SnapshotMap<&Addr, Uint128> = SnapshotMap::new(
+    "shares_supply",
+    "shares_supply__checkpoints",
+    "shares_supply__changelog",
+    Strategy::EveryBlock,
+);
+pub const SHARES_BALANCE: SnapshotMap<(&Addr, &Addr), Uint128> = SnapshotMap::new(
+    "shares_balance",
+    "shares_balance__checkpoints",
+    "shares_balance__changelog",
+    Strategy::EveryBlock,
+);
+// First height with snapshot history, set on instantiate or on the migration adding snapshots
+pub const SNAPSHOT_START_HEIGHT: Item<u64> = Item::new("snapshot_start_height");
+pub const SHARES_HOLDERS: Map<&Addr, Uint128> = Map::new("shares_holders");
+// Sorted by min_volume ascending, empty means the global referral fees apply
+pub const REFERRAL_TIERS: Item<Vec<ReferralTier>> = Item::new("referral_tiers");
+// Referred buy volume, self-referrals excluded
+pub const REFERRAL_VOLUME: Map<&Addr, Uint128> = Map::new("referral_volume");
+// Fees a subject chose for its own market, kept within SUBJECT_FEE_BOUNDS
+pub const SUBJECT_FEES: Map<&Addr, SubjectFee> = Map::new("subject_fees");
+pub const SUBJECT_FEE_BOUNDS: Item<SubjectFeeBounds> = Item::new("subject_fee_bounds");
+// Keyed by (role, account)
+pub const ROLES: Map<(&str, &Addr), bool> = Map::new("roles");
+// Timelock delay in seconds between proposing and executing a config change
+pub const CONFIG_CHANGE_DELAY: Item<u64> = Item::new("config_change_delay");
+pub const CONFIG_CHANGE_COUNT: Item<u64> = Item::new("config_change_count");
+pub const PENDING_CONFIG_CHANGES: Map<u64, PendingConfigChange> = Map::new("pending_config_changes");
+pub const MARKET_STATUS: Map<&Addr, MarketStatus> = Map::new("market_status");
+// Curve reserve backing the outstanding supply of each subject, in the quote denom
+pub const RESERVE: Map<&Addr, Uint128> = Map::new("reserve");
+pub const TOTAL_RESERVE: Item<Uint128> = Item::new("total_reserve");
+// Present while a migration is still rebuilding the reserves, trades wait until it is gone
+pub const RESERVE_MIGRATION: Item<ReserveMigration> = Item::new("reserve_migration");
+// Subjects whose shares can only move through the curve
+pub const TRANSFERS_DISABLED: Map<&Addr, bool> = Map::new("transfers_disabled");
+// Keyed by (owner, spender, shares_subject)
+pub const ALLOWANCES: Map<(&Addr, &Addr, &Addr), ShareAllowance> = Map::new("allowances");
+// Keyed by (owner, operator), operators can trade any subject for the owner
+pub const OPERATORS: Map<(&Addr, &Addr), Expiration> = Map::new("operators");
+pub const LAUNCH_PROTECTION: Map<&Addr, LaunchProtection> = Map::new("launch_protection");
+// Keyed by (shares_subject, buyer)
+pub const LAUNCH_ALLOWLIST: Map<(&Addr, &Addr), bool> = Map::new("launch_allowlist");
+pub const LAUNCH_PURCHASES: Map<(&Addr, &Addr), LaunchPurchases> = Map::new("launch_purchases");
+pub const TRADE_LIMITS: Item<TradeLimits> = Item::new("trade_limits");
+// Keyed by (holder, shares_subject) like SHARES_BALANCE
+pub const LAST_TRADES: Map<(&Addr, &Addr), LastTrade> = Map::new("last_trades");
+pub const CIRCUIT_BREAKER_CONFIG: Item<CircuitBreakerConfig> = Item::new("circuit_breaker_config");
+pub const CIRCUIT_BREAKERS: Map<&Addr, CircuitBreaker> = Map::new("circuit_breakers");
+// Addresses that may not trade or receive shares and fees
+pub const BLOCKED: Map<&Addr, bool> = Map::new("blocked");
+// Optional external contract answering ScreeningQueryMsg::IsBlocked
+pub const SCREENING_CONTRACT: Item<Addr> = Item::new("screening_contract");
+// Part of the subject fee paid to holders, same percent scale as the fees
+pub const HOLDER_REWARD_PERCENT: Map<&Addr, Uint128> = Map::new("holder_reward_percent");
+// Rewards paid per share of a subject since its first holder reward
+pub const HOLDER_REWARD_INDEX: Map<&Addr, Decimal256> = Map::new("holder_reward_index");
+// Keyed by (holder, shares_subject) like SHARES_BALANCE
+pub const HOLDER_REWARDS: Map<(&Addr, &Addr), HolderRewards> = Map::new("holder_rewards");
+// Holder rewards paid in and not claimed yet, owed on top of the reserve
+pub const TOTAL_HOLDER_REWARDS: Item<Uint128> = Item::new("total_holder_rewards");
+pub const AIRDROP_COUNT: Item<u64> = Item::new("airdrop_count");
+pub const AIRDROPS: Map<u64, Airdrop> = Map::new("airdrops");
+// Keyed by (airdrop id, holder)
+pub const AIRDROP_CLAIMS: Map<(u64, &Addr), Uint128> = Map::new("airdrop_claims");
+// Airdropped funds per denom not claimed or reclaimed yet
+pub const AIRDROP_BALANCES: Map<&str, Uint128> = Map::new("airdrop_balances");
+pub const LOCK_COUNT: Item<u64> = Item::new("lock_count");
+// Keyed by (owner, lock id)
+pub const LOCKS: Map<(&Addr, u64), ShareLock> = Map::new("locks");
+// Keyed by (holder, shares_subject) like SHARES_BALANCE, held until the lock is released
+pub const LOCKED_SHARES: Map<(&Addr, &Addr), Uint128> = Map::new("locked_shares");
+// Keyed by (shares_subject, lock id) to the lock owner, for summing the weight of a subject
+pub const SUBJECT_LOCKS: Map<(&Addr, u64), Addr> = Map::new("subject_locks");
+// Vesting schedule a subject put on its own shares
+pub const SELF_VESTING: Map<&Addr, SelfVesting> = Map::new("self_vesting");
+pub const POLL_COUNT: Item<u64> = Item::new("poll_count");
+pub const POLLS: Map<u64, Poll> = Map::new("polls");
+// Keyed by (poll id, voter)
+pub const POLL_VOTES: Map<(u64, &Addr), PollVote> = Map::new("poll_votes");
\ No newline at end of file
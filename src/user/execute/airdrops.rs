@@ -0,0 +1,164 @@
+use cosmwasm_std::{
+    coins, Addr, BankMsg, DepsMut, Env, Event, MessageInfo, Response, StdError, StdResult, Storage,
+    Uint128,
+};
+use cw_utils::{one_coin, Expiration};
+
+use crate::{
+    state::{
+        Airdrop, AIRDROPS, AIRDROP_BALANCES, AIRDROP_CLAIMS, AIRDROP_COUNT, SHARES_BALANCE,
+        SHARES_SUPPLY,
+    },
+    util::ensure_exit_not_blocked,
+    ContractError,
+};
+
+// Splits the sent coin over the holders at the start of the current block
+pub fn fund_holder_airdrop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    shares_subject: Addr,
+    expires: Expiration,
+) -> Result<Response, ContractError> {
+    if info.sender != shares_subject {
+        return Err(ContractError::Unauthorized {});
+    }
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    let funds = one_coin(&info).map_err(|_| ContractError::InvalidTokenSentPayment {})?;
+    let supply = SHARES_SUPPLY
+        .may_load_at_height(deps.storage, &shares_subject, env.block.height)?
+        .unwrap_or_default();
+    if supply.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Market has no holders to airdrop to",
+        )));
+    }
+
+    let id = AIRDROP_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    AIRDROP_COUNT.save(deps.storage, &id)?;
+    AIRDROPS.save(
+        deps.storage,
+        id,
+        &Airdrop {
+            shares_subject: shares_subject.clone(),
+            denom: funds.denom.clone(),
+            amount: funds.amount,
+            claimed: Default::default(),
+            snapshot_height: env.block.height,
+            supply,
+            expires,
+            reclaimed: false,
+        },
+    )?;
+    update_airdrop_balance(deps.storage, &funds.denom, |balance| balance + funds.amount)?;
+
+    Ok(Response::new().add_event(
+        Event::new("fund_holder_airdrop")
+            .add_attribute("id", id.to_string())
+            .add_attribute("shares_subject", shares_subject)
+            .add_attribute("denom", funds.denom)
+            .add_attribute("amount", funds.amount)
+            .add_attribute("snapshot_height", env.block.height.to_string())
+            .add_attribute("expires", expires.to_string()),
+    ))
+}
+
+pub fn claim_airdrop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    ensure_exit_not_blocked(deps.as_ref(), &[&info.sender])?;
+    let mut airdrop = AIRDROPS
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::AirdropNotFound {})?;
+    if airdrop.expires.is_expired(&env.block) {
+        return Err(ContractError::AirdropExpired {});
+    }
+    let amount = get_airdrop_share(deps.storage, id, &airdrop, &info.sender)?;
+    if amount.is_zero() {
+        return Err(ContractError::NoAirdropShare {});
+    }
+    AIRDROP_CLAIMS.save(deps.storage, (id, &info.sender), &amount)?;
+    airdrop.claimed += amount;
+    AIRDROPS.save(deps.storage, id, &airdrop)?;
+    update_airdrop_balance(deps.storage, &airdrop.denom, |balance| balance - amount)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("claim_airdrop")
+                .add_attribute("id", id.to_string())
+                .add_attribute("holder", &info.sender)
+                .add_attribute("amount", amount),
+        )
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), airdrop.denom),
+        }))
+}
+
+// The subject takes back what holders left unclaimed once the airdrop expired
+pub fn reclaim_airdrop(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let mut airdrop = AIRDROPS
+        .may_load(deps.storage, id)?
+        .ok_or(ContractError::AirdropNotFound {})?;
+    if info.sender != airdrop.shares_subject {
+        return Err(ContractError::Unauthorized {});
+    }
+    if !airdrop.expires.is_expired(&env.block) {
+        return Err(ContractError::AirdropNotExpired {});
+    }
+    let amount = airdrop.amount - airdrop.claimed;
+    if airdrop.reclaimed || amount.is_zero() {
+        return Err(ContractError::NoAirdropShare {});
+    }
+    airdrop.reclaimed = true;
+    AIRDROPS.save(deps.storage, id, &airdrop)?;
+    update_airdrop_balance(deps.storage, &airdrop.denom, |balance| balance - amount)?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("reclaim_airdrop")
+                .add_attribute("id", id.to_string())
+                .add_attribute("shares_subject", &info.sender)
+                .add_attribute("amount", amount),
+        )
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), airdrop.denom),
+        }))
+}
+
+// Part of an airdrop a holder can claim, zero once claimed
+pub fn get_airdrop_share(
+    storage: &dyn Storage,
+    id: u64,
+    airdrop: &Airdrop,
+    holder: &Addr,
+) -> StdResult<Uint128> {
+    if AIRDROP_CLAIMS.has(storage, (id, holder)) {
+        return Ok(Uint128::zero());
+    }
+    let balance = SHARES_BALANCE
+        .may_load_at_height(storage, (holder, &airdrop.shares_subject), airdrop.snapshot_height)?
+        .unwrap_or_default();
+    Ok(airdrop.amount.multiply_ratio(balance, airdrop.supply))
+}
+
+fn update_airdrop_balance(
+    storage: &mut dyn Storage,
+    denom: &str,
+    action: impl FnOnce(Uint128) -> Uint128,
+) -> StdResult<()> {
+    let balance = AIRDROP_BALANCES.may_load(storage, denom)?.unwrap_or_default();
+    AIRDROP_BALANCES.save(storage, denom, &action(balance))
+}
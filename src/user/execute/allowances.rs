@@ -0,0 +1,192 @@
+use cosmwasm_std::{Addr, DepsMut, Env, Event, MessageInfo, Response, StdError, Uint128};
+use cw_utils::Expiration;
+
+use crate::{
+    state::{ShareAllowance, ALLOWANCES},
+    user::execute::sell_shares,
+    util::{
+        ensure_exit_not_blocked, ensure_not_blocked, move_shares, spend_allowance,
+        transfers_enabled,
+    },
+    ContractError,
+};
+
+const TX_AMOUNT_SHARES: Uint128 = Uint128::new(1);
+
+pub fn increase_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: Addr,
+    shares_subject: Addr,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let validated_spender_address = deps.api.addr_validate(spender.as_str())?;
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    if validated_spender_address == info.sender {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot set allowance to own account",
+        )));
+    }
+
+    let key = (&info.sender, &validated_spender_address, &validated_shares_subject_address);
+    let mut allowance = ALLOWANCES
+        .may_load(deps.storage, key)?
+        .unwrap_or(ShareAllowance {
+            allowance: Uint128::zero(),
+            expires: Expiration::Never {},
+        });
+    if let Some(expires) = expires {
+        if expires.is_expired(&env.block) {
+            return Err(ContractError::InvalidExpiration {});
+        }
+        allowance.expires = expires;
+    }
+    allowance.allowance = allowance.allowance.checked_add(amount).map_err(StdError::from)?;
+    ALLOWANCES.save(deps.storage, key, &allowance)?;
+
+    Ok(Response::new().add_event(
+        Event::new("increase_allowance")
+            .add_attribute("owner", info.sender)
+            .add_attribute("spender", validated_spender_address)
+            .add_attribute("shares_subject", validated_shares_subject_address)
+            .add_attribute("amount", amount)
+            .add_attribute("allowance", allowance.allowance),
+    ))
+}
+
+pub fn decrease_allowance(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    spender: Addr,
+    shares_subject: Addr,
+    amount: Uint128,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let validated_spender_address = deps.api.addr_validate(spender.as_str())?;
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    if validated_spender_address == info.sender {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot set allowance to own account",
+        )));
+    }
+
+    let key = (&info.sender, &validated_spender_address, &validated_shares_subject_address);
+    let mut allowance = ALLOWANCES
+        .may_load(deps.storage, key)?
+        .ok_or(ContractError::InsufficientAllowance {})?;
+    // Decreasing below zero simply removes the allowance
+    allowance.allowance = allowance.allowance.saturating_sub(amount);
+    if allowance.allowance.is_zero() {
+        ALLOWANCES.remove(deps.storage, key);
+    } else {
+        if let Some(expires) = expires {
+            if expires.is_expired(&env.block) {
+                return Err(ContractError::InvalidExpiration {});
+            }
+            allowance.expires = expires;
+        }
+        ALLOWANCES.save(deps.storage, key, &allowance)?;
+    }
+
+    Ok(Response::new().add_event(
+        Event::new("decrease_allowance")
+            .add_attribute("owner", info.sender)
+            .add_attribute("spender", validated_spender_address)
+            .add_attribute("shares_subject", validated_shares_subject_address)
+            .add_attribute("amount", amount)
+            .add_attribute("allowance", allowance.allowance),
+    ))
+}
+
+pub fn transfer_shares_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: Addr,
+    shares_subject: Addr,
+    recipient: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let validated_owner_address = deps.api.addr_validate(owner.as_str())?;
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    let validated_recipient_address = deps.api.addr_validate(recipient.as_str())?;
+
+    if !transfers_enabled(deps.storage, &validated_shares_subject_address)? {
+        return Err(ContractError::TransfersDisabled {});
+    }
+    ensure_not_blocked(
+        deps.as_ref(),
+        &[&info.sender, &validated_owner_address, &validated_recipient_address],
+    )?;
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot transfer zero shares",
+        )));
+    }
+    if validated_recipient_address == validated_owner_address {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot transfer shares to the owner",
+        )));
+    }
+
+    spend_allowance(
+        deps.storage,
+        &env.block,
+        &validated_owner_address,
+        &info.sender,
+        &validated_shares_subject_address,
+        amount,
+    )?;
+    move_shares(
+        deps.storage,
+        &env.block,
+        &validated_shares_subject_address,
+        &validated_owner_address,
+        &validated_recipient_address,
+        amount,
+    )?;
+
+    Ok(Response::new().add_event(
+        Event::new("transfer_shares_from")
+            .add_attribute("spender", info.sender)
+            .add_attribute("owner", validated_owner_address)
+            .add_attribute("recipient", validated_recipient_address)
+            .add_attribute("shares_subject", validated_shares_subject_address)
+            .add_attribute("amount", amount),
+    ))
+}
+
+// Sells one share out of the owner's balance, proceeds go to the owner
+pub fn sell_shares_from(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: Addr,
+    shares_subject: Addr,
+    referral: Addr,
+) -> Result<Response, ContractError> {
+    let validated_owner_address = deps.api.addr_validate(owner.as_str())?;
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+
+    spend_allowance(
+        deps.storage,
+        &env.block,
+        &validated_owner_address,
+        &info.sender,
+        &validated_shares_subject_address,
+        TX_AMOUNT_SHARES,
+    )?;
+    ensure_exit_not_blocked(deps.as_ref(), &[&info.sender])?;
+    let response = sell_shares(
+        deps,
+        env,
+        validated_owner_address.clone(),
+        validated_owner_address,
+        validated_shares_subject_address,
+        referral,
+    )?;
+    Ok(response.add_attribute("spender", info.sender))
+}
@@ -0,0 +1,182 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use cosmwasm_std::{
+    coins, Addr, BankMsg, DepsMut, Env, Event, MessageInfo, Response, StdError, Uint128,
+};
+use cw_utils::may_pay;
+
+use crate::{
+    msg::TradeOp,
+    state::STATE,
+    user::execute::{apply_buy, apply_sell},
+    util::{check_circuit_breaker, ensure_exit_not_blocked, ensure_not_blocked, OUT_DENOM},
+    ContractError,
+};
+
+// Every share is settled on its own, so the batch size bounds the gas used
+const MAX_BATCH_OP_SHARES: Uint128 = Uint128::new(50);
+const MAX_BATCH_SHARES: Uint128 = Uint128::new(100);
+
+fn ensure_batch_size(trades: &[TradeOp]) -> Result<(), ContractError> {
+    let mut total_shares = Uint128::zero();
+    for trade in trades {
+        let (TradeOp::Buy { amount, .. } | TradeOp::Sell { amount, .. }) = trade;
+        if amount.is_zero() {
+            return Err(ContractError::Std(StdError::generic_err("Cannot trade zero shares")));
+        }
+        total_shares += amount;
+        if *amount > MAX_BATCH_OP_SHARES || total_shares > MAX_BATCH_SHARES {
+            return Err(ContractError::BatchTooLarge {
+                max_per_op: MAX_BATCH_OP_SHARES.to_string(),
+                max_total: MAX_BATCH_SHARES.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+// Runs every op or none, buys are paid from info.funds plus the batch's own sell proceeds
+pub fn batch_trade(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    trades: Vec<TradeOp>,
+) -> Result<Response, ContractError> {
+    if trades.is_empty() {
+        return Err(ContractError::Std(StdError::generic_err("Batch has no trades")));
+    }
+    ensure_batch_size(&trades)?;
+    let paid = may_pay(&info, OUT_DENOM).map_err(|_| ContractError::InvalidTokenSentPayment {})?;
+    ensure_exit_not_blocked(deps.as_ref(), &[&info.sender])?;
+    let state = STATE.load(deps.storage)?;
+
+    let mut payouts: BTreeMap<Addr, Uint128> = BTreeMap::new();
+    let mut total_cost = Uint128::zero();
+    let mut total_proceeds = Uint128::zero();
+    let mut events: Vec<Event> = Vec::new();
+    let mut traded_subjects: BTreeSet<Addr> = BTreeSet::new();
+
+    for trade in trades {
+        match trade {
+            TradeOp::Buy {
+                shares_subject,
+                referral,
+                amount,
+                max_cost,
+            } => {
+                let validated_shares_subject_address =
+                    deps.api.addr_validate(shares_subject.as_str())?;
+                let validated_referral_address = deps.api.addr_validate(referral.as_str())?;
+                ensure_not_blocked(
+                    deps.as_ref(),
+                    &[
+                        &info.sender,
+                        &validated_shares_subject_address,
+                        &validated_referral_address,
+                    ],
+                )?;
+                let mut cost = Uint128::zero();
+                for _ in 0..amount.u128() {
+                    let settlement = apply_buy(
+                        deps.storage,
+                        &env.block,
+                        &state,
+                        &info.sender,
+                        &info.sender,
+                        &validated_shares_subject_address,
+                        &validated_referral_address,
+                    )?;
+                    cost += settlement.total;
+                    for (recipient, payout) in settlement.payouts {
+                        *payouts.entry(recipient).or_default() += payout;
+                    }
+                }
+                if cost > max_cost {
+                    return Err(ContractError::SlippageExceeded {});
+                }
+                total_cost += cost;
+                traded_subjects.insert(validated_shares_subject_address.clone());
+                events.push(
+                    Event::new("batch_trade_op")
+                        .add_attribute("side", "buy")
+                        .add_attribute("shares_subject", validated_shares_subject_address)
+                        .add_attribute("amount", amount)
+                        .add_attribute("total", cost),
+                );
+            }
+            TradeOp::Sell {
+                shares_subject,
+                referral,
+                amount,
+                min_proceeds,
+            } => {
+                let validated_shares_subject_address =
+                    deps.api.addr_validate(shares_subject.as_str())?;
+                let validated_referral_address = deps.api.addr_validate(referral.as_str())?;
+                ensure_exit_not_blocked(
+                    deps.as_ref(),
+                    &[&validated_shares_subject_address, &validated_referral_address],
+                )?;
+                let mut proceeds = Uint128::zero();
+                for _ in 0..amount.u128() {
+                    let settlement = apply_sell(
+                        deps.storage,
+                        &env.block,
+                        &state,
+                        &info.sender,
+                        &info.sender,
+                        &validated_shares_subject_address,
+                        &validated_referral_address,
+                    )?;
+                    proceeds += settlement.total;
+                    for (recipient, payout) in settlement.payouts {
+                        *payouts.entry(recipient).or_default() += payout;
+                    }
+                }
+                if proceeds < min_proceeds {
+                    return Err(ContractError::SlippageExceeded {});
+                }
+                total_proceeds += proceeds;
+                traded_subjects.insert(validated_shares_subject_address.clone());
+                events.push(
+                    Event::new("batch_trade_op")
+                        .add_attribute("side", "sell")
+                        .add_attribute("shares_subject", validated_shares_subject_address)
+                        .add_attribute("amount", amount)
+                        .add_attribute("total", proceeds),
+                );
+            }
+        }
+    }
+
+    for shares_subject in traded_subjects {
+        events.extend(check_circuit_breaker(deps.storage, &env.block, &shares_subject)?);
+    }
+
+    // Net payment check, anything owed to the sender counts towards the buys
+    let sender_payout = payouts.remove(&info.sender).unwrap_or_default() + paid;
+    if sender_payout < total_cost {
+        return Err(ContractError::InsufficientPayment {});
+    }
+    payouts.insert(info.sender.clone(), sender_payout - total_cost);
+
+    let msgs: Vec<BankMsg> = payouts
+        .into_iter()
+        .filter(|(_, payout)| !payout.is_zero())
+        .map(|(recipient, payout)| BankMsg::Send {
+            to_address: recipient.to_string(),
+            amount: coins(payout.into(), OUT_DENOM),
+        })
+        .collect();
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("batch_trade")
+                .add_attribute("sender", info.sender)
+                .add_attribute("total_cost", total_cost)
+                .add_attribute("total_proceeds", total_proceeds)
+                .add_attribute("funds", paid),
+        )
+        .add_events(events)
+        .add_messages(msgs))
+}
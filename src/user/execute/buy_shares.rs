@@ -1,20 +1,27 @@
 use crate::{
-    state::{SHARES_BALANCE, SHARES_HOLDERS, SHARES_SUPPLY, STATE},
-    util::{calculate_fee, get_price},
+    state::{State, SHARES_BALANCE, SHARES_HOLDERS, SHARES_SUPPLY, STATE},
+    util::{
+        add_referral_volume, add_reserve, calculate_fee, check_circuit_breaker,
+        check_launch_protection, distribute_holder_rewards, ensure_circuit_breaker,
+        ensure_not_blocked, ensure_reserves_migrated, get_price, get_referral_fee_percent,
+        get_market_status, get_subject_fee_percent, record_trade, settle_holder_rewards,
+        split_protocol_fee, OUT_DENOM,
+    },
     ContractError,
 };
-use cosmwasm_std::{coins, Addr, BankMsg, Event, StdResult, Uint128};
-use cosmwasm_std::{DepsMut, MessageInfo, Response};
+use cosmwasm_std::{coins, Addr, BankMsg, BlockInfo, Event, StdResult, Storage, Uint128};
+use cosmwasm_std::{DepsMut, Env, MessageInfo, Response};
 use cw_utils::must_pay;
 
-const OUT_DENOM: &str = "inj";
-
 const BASE_SUPPLY: Uint128 = Uint128::new(1);
 
-fn increment_share_holders(deps: DepsMut, shares_subject: Addr) -> Result<(), ContractError> {
+fn increment_share_holders(
+    storage: &mut dyn Storage,
+    shares_subject: &Addr,
+) -> Result<(), ContractError> {
     SHARES_HOLDERS.update(
-        deps.storage,
-        &shares_subject,
+        storage,
+        shares_subject,
         |holders: Option<Uint128>| -> StdResult<_> {
             Ok(holders.unwrap_or_default() + Uint128::new(1))
         },
@@ -22,91 +29,160 @@ fn increment_share_holders(deps: DepsMut, shares_subject: Addr) -> Result<(), Co
     Ok(())
 }
 
-pub fn buy_shares(
-    deps: DepsMut,
-    info: MessageInfo,
-    shares_subject: Addr,
-    referral: Addr,
-) -> Result<Response, ContractError> {
-    let validated_referral_address = deps.api.addr_validate(&referral.to_string())?;
-    let validated_shares_subject_address = deps.api.addr_validate(&shares_subject.to_string())?;
+// Result of a single share buy, the caller collects `total` and settles `payouts`
+pub struct BuySettlement {
+    pub total: Uint128,
+    pub subject_fee: Uint128,
+    pub holder_reward: Uint128,
+    pub referral_fee: Uint128,
+    pub shares_balance_new: Uint128,
+    pub shares_supply_new: Uint128,
+    pub payouts: Vec<(Addr, Uint128)>,
+}
 
-    let state = STATE.load(deps.storage)?;
+// Credits one share to the recipient, expects validated addresses.
+// Trade and launch limits apply to the payer, who may differ from the recipient.
+pub fn apply_buy(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    state: &State,
+    payer: &Addr,
+    recipient: &Addr,
+    shares_subject: &Addr,
+    referral: &Addr,
+) -> Result<BuySettlement, ContractError> {
+    let market_status = get_market_status(storage, shares_subject)?;
+    if !market_status.can_buy() {
+        return Err(ContractError::MarketBuysDisabled {
+            status: market_status.as_str().to_string(),
+        });
+    }
+    ensure_reserves_migrated(storage)?;
+    ensure_circuit_breaker(storage, block, shares_subject)?;
+    check_launch_protection(storage, block, shares_subject, payer, recipient)?;
+    record_trade(storage, block, payer, shares_subject, true)?;
 
     let shares_supply = BASE_SUPPLY
         + SHARES_SUPPLY
-            .may_load(deps.storage, &validated_shares_subject_address)?
+            .may_load(storage, shares_subject)?
             .unwrap_or_default();
 
     let shares_balance = SHARES_BALANCE
-        .may_load(
-            deps.storage,
-            (&info.sender, &validated_shares_subject_address),
-        )?
+        .may_load(storage, (recipient, shares_subject))?
         .unwrap_or_default();
-    
+
     let price = get_price(shares_supply);
+    let (_, subject_buy_fee_percent, _) = get_subject_fee_percent(storage, state, shares_subject)?;
+    let (_, referral_buy_fee_percent, _) = get_referral_fee_percent(storage, state, referral)?;
 
     let protocol_fee = calculate_fee(price, state.protocol_buy_fee_percent);
-    let subject_fee = calculate_fee(price, state.subject_buy_fee_percent);
-    let referral_fee = calculate_fee(price, state.referral_buy_fee_percent);
+    let subject_fee = calculate_fee(price, subject_buy_fee_percent);
+    let referral_fee = calculate_fee(price, referral_buy_fee_percent);
     let total = price + protocol_fee + subject_fee + referral_fee;
 
-    must_pay(&info, OUT_DENOM).map_err(|_| ContractError::InvalidTokenSentPayment {})?;
-    assert!(info.funds[0].amount >= total, "Insufficient payment");
+    let holder_reward = distribute_holder_rewards(storage, shares_subject, subject_fee)?;
+    let subject_fee = subject_fee - holder_reward;
+    settle_holder_rewards(storage, recipient, shares_subject)?;
+
     SHARES_BALANCE.update(
-        deps.storage,
-        (&info.sender, &validated_shares_subject_address),
+        storage,
+        (recipient, shares_subject),
+        block.height,
         |balance: Option<Uint128>| -> StdResult<_> {
             Ok(balance.unwrap_or_default() + Uint128::new(1))
         },
     )?;
 
     SHARES_SUPPLY.update(
-        deps.storage,
-        &validated_shares_subject_address,
+        storage,
+        shares_subject,
+        block.height,
         |supply: Option<Uint128>| -> StdResult<_> {
             Ok(supply.unwrap_or_default() + Uint128::new(1))
         },
     )?;
 
+    add_referral_volume(storage, referral, payer, shares_subject, price)?;
+    add_reserve(storage, shares_subject, price)?;
+
     // If is first buy, add as a holder
     if shares_balance.is_zero() {
-        increment_share_holders(deps, validated_shares_subject_address.clone())?;
+        increment_share_holders(storage, shares_subject)?;
     }
 
-    let mut msgs: Vec<BankMsg> = Vec::new();
+    let mut payouts = split_protocol_fee(&state.protocol_fee_recipients, protocol_fee);
+    payouts.push((shares_subject.clone(), subject_fee));
+    payouts.push((referral.clone(), referral_fee));
+
+    Ok(BuySettlement {
+        total,
+        subject_fee,
+        holder_reward,
+        referral_fee,
+        shares_balance_new: shares_balance + Uint128::new(1),
+        shares_supply_new: shares_supply + Uint128::new(1),
+        payouts,
+    })
+}
 
-    if protocol_fee > Uint128::zero() {
-        let protocol_fee_result = BankMsg::Send {
-            to_address: state.protocol_fee_destination.to_string(),
-            amount: coins(protocol_fee.into(), OUT_DENOM),
-        };
-        msgs.push(protocol_fee_result);
-    }
+pub fn buy_shares(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    recipient: Addr,
+    refund_recipient: Addr,
+    shares_subject: Addr,
+    referral: Addr,
+) -> Result<Response, ContractError> {
+    let validated_referral_address = deps.api.addr_validate(&referral.to_string())?;
+    let validated_shares_subject_address = deps.api.addr_validate(&shares_subject.to_string())?;
+    ensure_not_blocked(
+        deps.as_ref(),
+        &[
+            &info.sender,
+            &recipient,
+            &refund_recipient,
+            &validated_shares_subject_address,
+            &validated_referral_address,
+        ],
+    )?;
 
-    if subject_fee > Uint128::zero() {
-        let subject_fee_result = BankMsg::Send {
-            to_address: validated_shares_subject_address.to_string(),
-            amount: coins(subject_fee.into(), OUT_DENOM),
-        };
-        msgs.push(subject_fee_result);
-    }
+    let state = STATE.load(deps.storage)?;
 
-    if referral_fee > Uint128::zero() {
-        let referral_fee_result = BankMsg::Send {
-            to_address: validated_referral_address.to_string(),
-            amount: coins(referral_fee.into(), OUT_DENOM),
-        };
-        msgs.push(referral_fee_result);
+    let settlement = apply_buy(
+        deps.storage,
+        &env.block,
+        &state,
+        &info.sender,
+        &recipient,
+        &validated_shares_subject_address,
+        &validated_referral_address,
+    )?;
+    let total = settlement.total;
+    let circuit_breaker_event =
+        check_circuit_breaker(deps.storage, &env.block, &validated_shares_subject_address)?;
+
+    must_pay(&info, OUT_DENOM).map_err(|_| ContractError::InvalidTokenSentPayment {})?;
+    if info.funds[0].amount < total {
+        return Err(ContractError::InsufficientPayment {});
     }
 
-    let shares_balance_new: Uint128 = shares_balance + Uint128::new(1);
+    let mut msgs: Vec<BankMsg> = Vec::new();
+
+    for (payout_recipient, payout) in settlement.payouts {
+        if payout > Uint128::zero() {
+            let payout_result = BankMsg::Send {
+                to_address: payout_recipient.to_string(),
+                amount: coins(payout.into(), OUT_DENOM),
+            };
+            msgs.push(payout_result);
+        }
+    }
 
     let return_payment = info.funds[0].amount - total;
     if return_payment > Uint128::zero() {
         let return_payment_result = BankMsg::Send {
-            to_address: info.sender.to_string(),
+            to_address: refund_recipient.to_string(),
             amount: coins(return_payment.into(), OUT_DENOM),
         };
         msgs.push(return_payment_result);
@@ -115,16 +191,19 @@ pub fn buy_shares(
         .add_event(
             Event::new("buy_shares")
                 .add_attribute("sender", info.sender)
+                .add_attribute("recipient", recipient)
                 .add_attribute("shares_subject", validated_shares_subject_address)
                 .add_attribute("amount", Uint128::new(1))
-                .add_attribute("shares_balance_new", shares_balance_new)
-                .add_attribute("shares_supply_new", shares_supply + Uint128::new(1))
-                .add_attribute("subject_fees", subject_fee)
-                .add_attribute("referral_fees", referral_fee)
+                .add_attribute("shares_balance_new", settlement.shares_balance_new)
+                .add_attribute("shares_supply_new", settlement.shares_supply_new)
+                .add_attribute("subject_fees", settlement.subject_fee)
+                .add_attribute("holder_rewards", settlement.holder_reward)
+                .add_attribute("referral_fees", settlement.referral_fee)
                 .add_attribute("referral", validated_referral_address)
                 .add_attribute("total", total)
                 .add_attribute("funds", info.funds[0].amount),
         )
+        .add_events(circuit_breaker_event)
         .add_messages(msgs);
     return Ok(response);
 }
@@ -0,0 +1,68 @@
+use cosmwasm_std::{coins, Addr, BankMsg, DepsMut, Event, MessageInfo, Response, StdError, Uint128};
+
+use crate::{
+    state::{HOLDER_REWARDS, HOLDER_REWARD_PERCENT, TOTAL_HOLDER_REWARDS},
+    util::{ensure_exit_not_blocked, settle_holder_rewards, FEE_SCALE, OUT_DENOM},
+    ContractError,
+};
+
+pub fn validate_holder_reward_percent(percent: Uint128) -> Result<(), ContractError> {
+    if percent > FEE_SCALE {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Holder reward percent cannot exceed the whole subject fee",
+        )));
+    }
+    Ok(())
+}
+
+// Part of the sender's subject fee that goes to its holders, runs through ProposeConfigChange
+// and applies from the next trade after it executes
+pub fn set_holder_reward_percent(
+    deps: DepsMut,
+    info: MessageInfo,
+    percent: Uint128,
+) -> Result<Response, ContractError> {
+    validate_holder_reward_percent(percent)?;
+    HOLDER_REWARD_PERCENT.save(deps.storage, &info.sender, &percent)?;
+    Ok(Response::new().add_event(
+        Event::new("set_holder_reward_percent")
+            .add_attribute("shares_subject", info.sender)
+            .add_attribute("percent", percent),
+    ))
+}
+
+pub fn claim_holder_rewards(
+    deps: DepsMut,
+    info: MessageInfo,
+    shares_subject: Addr,
+) -> Result<Response, ContractError> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    ensure_exit_not_blocked(deps.as_ref(), &[&info.sender])?;
+
+    let mut rewards =
+        settle_holder_rewards(deps.storage, &info.sender, &validated_shares_subject_address)?;
+    let amount = rewards.pending;
+    if amount.is_zero() {
+        return Err(ContractError::NoHolderRewards {});
+    }
+    rewards.pending = Uint128::zero();
+    HOLDER_REWARDS.save(
+        deps.storage,
+        (&info.sender, &validated_shares_subject_address),
+        &rewards,
+    )?;
+    let total_holder_rewards = TOTAL_HOLDER_REWARDS.load(deps.storage)?;
+    TOTAL_HOLDER_REWARDS.save(deps.storage, &(total_holder_rewards - amount))?;
+
+    Ok(Response::new()
+        .add_event(
+            Event::new("claim_holder_rewards")
+                .add_attribute("holder", &info.sender)
+                .add_attribute("shares_subject", validated_shares_subject_address)
+                .add_attribute("amount", amount),
+        )
+        .add_message(BankMsg::Send {
+            to_address: info.sender.to_string(),
+            amount: coins(amount.u128(), OUT_DENOM),
+        }))
+}
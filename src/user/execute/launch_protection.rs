@@ -0,0 +1,73 @@
+use cosmwasm_std::{Addr, DepsMut, Event, MessageInfo, Response, StdError, Uint128};
+use cw_utils::Duration;
+
+use crate::{
+    state::{LaunchProtection, LAUNCH_ALLOWLIST, LAUNCH_PROTECTION, SHARES_SUPPLY},
+    ContractError,
+};
+
+// Only possible before the first share of the sender's market is bought
+pub fn set_launch_protection(
+    deps: DepsMut,
+    info: MessageInfo,
+    window: Duration,
+    max_shares_per_address: Option<Uint128>,
+    min_buy_interval: Option<Duration>,
+    allowlist_only: bool,
+) -> Result<Response, ContractError> {
+    let supply = SHARES_SUPPLY
+        .may_load(deps.storage, &info.sender)?
+        .unwrap_or_default();
+    if !supply.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Market has already launched",
+        )));
+    }
+    if max_shares_per_address == Some(Uint128::zero()) {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Launch cap must be greater than zero",
+        )));
+    }
+
+    LAUNCH_PROTECTION.save(
+        deps.storage,
+        &info.sender,
+        &LaunchProtection {
+            window,
+            max_shares_per_address,
+            min_buy_interval,
+            allowlist_only,
+            ends: None,
+        },
+    )?;
+
+    Ok(Response::new().add_event(
+        Event::new("set_launch_protection")
+            .add_attribute("shares_subject", info.sender)
+            .add_attribute("window", window.to_string())
+            .add_attribute("allowlist_only", allowlist_only.to_string()),
+    ))
+}
+
+pub fn update_launch_allowlist(
+    deps: DepsMut,
+    info: MessageInfo,
+    add: Vec<Addr>,
+    remove: Vec<Addr>,
+) -> Result<Response, ContractError> {
+    for address in &add {
+        let validated_address = deps.api.addr_validate(address.as_str())?;
+        LAUNCH_ALLOWLIST.save(deps.storage, (&info.sender, &validated_address), &true)?;
+    }
+    for address in &remove {
+        let validated_address = deps.api.addr_validate(address.as_str())?;
+        LAUNCH_ALLOWLIST.remove(deps.storage, (&info.sender, &validated_address));
+    }
+
+    Ok(Response::new().add_event(
+        Event::new("update_launch_allowlist")
+            .add_attribute("shares_subject", info.sender)
+            .add_attribute("added", add.len().to_string())
+            .add_attribute("removed", remove.len().to_string()),
+    ))
+}
@@ -0,0 +1,128 @@
+use cosmwasm_std::{
+    Addr, DepsMut, Env, Event, MessageInfo, Response, StdError, StdResult, Storage, Uint128,
+};
+
+use crate::{
+    state::{ShareLock, LOCKED_SHARES, LOCKS, LOCK_COUNT, SHARES_BALANCE, SUBJECT_LOCKS},
+    ContractError,
+};
+
+// A locked share weighs LOCK_WEIGHT_SCALE, boosted linearly up to LOCK_MAX_BOOST times
+// at LOCK_MAX_DURATION
+const LOCK_WEIGHT_SCALE: u64 = 1000;
+const LOCK_MAX_DURATION: u64 = 365 * 24 * 60 * 60;
+const LOCK_MAX_BOOST: u64 = 2;
+
+// Duration is in seconds, locked shares stay with the owner but cannot be sold or transferred
+pub fn lock_shares(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    shares_subject: Addr,
+    amount: Uint128,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Lock amount must be greater than zero",
+        )));
+    }
+    if duration == 0 || duration > LOCK_MAX_DURATION {
+        return Err(ContractError::Std(StdError::generic_err(format!(
+            "Lock duration must be between 1 and {} seconds",
+            LOCK_MAX_DURATION
+        ))));
+    }
+    let balance = SHARES_BALANCE
+        .may_load(deps.storage, (&info.sender, &validated_shares_subject_address))?
+        .unwrap_or_default();
+    ensure_unlocked(
+        deps.storage,
+        &info.sender,
+        &validated_shares_subject_address,
+        balance,
+        amount,
+    )?;
+
+    let lock = ShareLock {
+        shares_subject: validated_shares_subject_address.clone(),
+        amount,
+        weight: get_lock_weight(amount, duration),
+        unlocks_at: env.block.time.plus_seconds(duration),
+    };
+    let id = LOCK_COUNT.may_load(deps.storage)?.unwrap_or_default() + 1;
+    LOCK_COUNT.save(deps.storage, &id)?;
+    LOCKS.save(deps.storage, (&info.sender, id), &lock)?;
+    LOCKED_SHARES.update(
+        deps.storage,
+        (&info.sender, &validated_shares_subject_address),
+        |locked: Option<Uint128>| -> StdResult<_> { Ok(locked.unwrap_or_default() + amount) },
+    )?;
+    SUBJECT_LOCKS.save(deps.storage, (&validated_shares_subject_address, id), &info.sender)?;
+
+    Ok(Response::new().add_event(
+        Event::new("lock_shares")
+            .add_attribute("id", id.to_string())
+            .add_attribute("owner", info.sender)
+            .add_attribute("shares_subject", validated_shares_subject_address)
+            .add_attribute("amount", amount)
+            .add_attribute("weight", lock.weight)
+            .add_attribute("unlocks_at", lock.unlocks_at.to_string()),
+    ))
+}
+
+pub fn unlock_shares(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    id: u64,
+) -> Result<Response, ContractError> {
+    let lock = LOCKS
+        .may_load(deps.storage, (&info.sender, id))?
+        .ok_or(ContractError::LockNotFound {})?;
+    if env.block.time < lock.unlocks_at {
+        return Err(ContractError::LockNotExpired {
+            unlocks_at: lock.unlocks_at.to_string(),
+        });
+    }
+    LOCKS.remove(deps.storage, (&info.sender, id));
+    LOCKED_SHARES.update(
+        deps.storage,
+        (&info.sender, &lock.shares_subject),
+        |locked: Option<Uint128>| -> StdResult<_> { Ok(locked.unwrap_or_default() - lock.amount) },
+    )?;
+    SUBJECT_LOCKS.remove(deps.storage, (&lock.shares_subject, id));
+
+    Ok(Response::new().add_event(
+        Event::new("unlock_shares")
+            .add_attribute("id", id.to_string())
+            .add_attribute("owner", info.sender)
+            .add_attribute("shares_subject", lock.shares_subject)
+            .add_attribute("amount", lock.amount),
+    ))
+}
+
+fn get_lock_weight(amount: Uint128, duration: u64) -> Uint128 {
+    let boost = LOCK_MAX_DURATION + (LOCK_MAX_BOOST - 1) * duration;
+    amount.multiply_ratio(LOCK_WEIGHT_SCALE * boost, LOCK_MAX_DURATION)
+}
+
+// New locks have to come from the shares not locked yet
+fn ensure_unlocked(
+    storage: &dyn Storage,
+    holder: &Addr,
+    shares_subject: &Addr,
+    balance: Uint128,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let locked = LOCKED_SHARES
+        .may_load(storage, (holder, shares_subject))?
+        .unwrap_or_default();
+    if balance.saturating_sub(locked) < amount {
+        return Err(ContractError::SharesLocked {
+            locked: locked.to_string(),
+        });
+    }
+    Ok(())
+}
@@ -1,5 +1,39 @@
 mod buy_shares;
-pub use buy_shares::buy_shares;
+pub use buy_shares::{apply_buy, buy_shares};
 
 mod sell_shares;
-pub use sell_shares::sell_shares;
\ No newline at end of file
+pub use sell_shares::{apply_sell, sell_shares};
+
+mod set_my_subject_fee;
+pub use set_my_subject_fee::{set_my_subject_fee, validate_my_subject_fee};
+
+mod transfer_shares;
+pub use transfer_shares::transfer_shares;
+
+mod allowances;
+pub use allowances::{
+    decrease_allowance, increase_allowance, sell_shares_from, transfer_shares_from,
+};
+
+mod operators;
+pub use operators::{approve_operator, buy_shares_for, revoke_operator, sell_shares_for};
+
+mod batch_trade;
+pub use batch_trade::batch_trade;
+
+mod launch_protection;
+pub use launch_protection::{set_launch_protection, update_launch_allowlist};
+
+mod holder_rewards;
+pub use holder_rewards::{
+    claim_holder_rewards, set_holder_reward_percent, validate_holder_reward_percent,
+};
+
+mod airdrops;
+pub use airdrops::{claim_airdrop, fund_holder_airdrop, get_airdrop_share, reclaim_airdrop};
+
+mod locks;
+pub use locks::{lock_shares, unlock_shares};
+
+mod self_vesting;
+pub use self_vesting::set_self_vesting;
\ No newline at end of file
@@ -0,0 +1,101 @@
+use cosmwasm_std::{Addr, DepsMut, Env, Event, MessageInfo, Response, StdError};
+use cw_utils::Expiration;
+
+use crate::{
+    state::OPERATORS,
+    user::execute::{buy_shares, sell_shares},
+    util::{ensure_exit_not_blocked, ensure_operator},
+    ContractError,
+};
+
+pub fn approve_operator(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    operator: Addr,
+    expires: Option<Expiration>,
+) -> Result<Response, ContractError> {
+    let validated_operator_address = deps.api.addr_validate(operator.as_str())?;
+    if validated_operator_address == info.sender {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot approve yourself as operator",
+        )));
+    }
+    let expires = expires.unwrap_or_default();
+    if expires.is_expired(&env.block) {
+        return Err(ContractError::InvalidExpiration {});
+    }
+    OPERATORS.save(deps.storage, (&info.sender, &validated_operator_address), &expires)?;
+
+    Ok(Response::new().add_event(
+        Event::new("approve_operator")
+            .add_attribute("owner", info.sender)
+            .add_attribute("operator", validated_operator_address)
+            .add_attribute("expires", expires.to_string()),
+    ))
+}
+
+pub fn revoke_operator(
+    deps: DepsMut,
+    info: MessageInfo,
+    operator: Addr,
+) -> Result<Response, ContractError> {
+    let validated_operator_address = deps.api.addr_validate(operator.as_str())?;
+    if !OPERATORS.has(deps.storage, (&info.sender, &validated_operator_address)) {
+        return Err(ContractError::OperatorNotApproved {});
+    }
+    OPERATORS.remove(deps.storage, (&info.sender, &validated_operator_address));
+
+    Ok(Response::new().add_event(
+        Event::new("revoke_operator")
+            .add_attribute("owner", info.sender)
+            .add_attribute("operator", validated_operator_address),
+    ))
+}
+
+// Operator pays, the owner receives the share and any refund
+pub fn buy_shares_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: Addr,
+    shares_subject: Addr,
+    referral: Addr,
+) -> Result<Response, ContractError> {
+    let validated_owner_address = deps.api.addr_validate(owner.as_str())?;
+    ensure_operator(deps.storage, &env.block, &validated_owner_address, &info.sender)?;
+    let operator = info.sender.clone();
+    let response = buy_shares(
+        deps,
+        env,
+        info,
+        validated_owner_address.clone(),
+        validated_owner_address,
+        shares_subject,
+        referral,
+    )?;
+    Ok(response.add_attribute("operator", operator))
+}
+
+// Sells one of the owner's shares, proceeds go to the owner
+pub fn sell_shares_for(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    owner: Addr,
+    shares_subject: Addr,
+    referral: Addr,
+) -> Result<Response, ContractError> {
+    let validated_owner_address = deps.api.addr_validate(owner.as_str())?;
+    ensure_operator(deps.storage, &env.block, &validated_owner_address, &info.sender)?;
+    ensure_exit_not_blocked(deps.as_ref(), &[&info.sender])?;
+    let response = sell_shares(
+        deps,
+        env,
+        validated_owner_address.clone(),
+        validated_owner_address,
+        shares_subject,
+        referral,
+    )?;
+    Ok(response.add_attribute("operator", info.sender))
+}
@@ -0,0 +1,55 @@
+use cosmwasm_std::{DepsMut, Env, Event, MessageInfo, Response, StdError, Uint128};
+
+use crate::{
+    state::{SelfVesting, SELF_VESTING, SHARES_BALANCE},
+    util::get_unvested_amount,
+    ContractError,
+};
+
+// Puts part of the sender's own shares under vesting, starting now. A running schedule
+// cannot be replaced, so holders can rely on it until it has fully vested.
+pub fn set_self_vesting(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    amount: Uint128,
+    cliff: u64,
+    duration: u64,
+) -> Result<Response, ContractError> {
+    if amount.is_zero() || duration == 0 || cliff > duration {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Vesting needs an amount, a duration and a cliff within the duration",
+        )));
+    }
+    let unvested = get_unvested_amount(deps.storage, &env.block, &info.sender, &info.sender)?;
+    if !unvested.is_zero() {
+        return Err(ContractError::SharesNotVested {
+            unvested: unvested.to_string(),
+        });
+    }
+    let balance = SHARES_BALANCE
+        .may_load(deps.storage, (&info.sender, &info.sender))?
+        .unwrap_or_default();
+    if balance < amount {
+        return Err(ContractError::Std(StdError::generic_err("Insufficient shares")));
+    }
+
+    SELF_VESTING.save(
+        deps.storage,
+        &info.sender,
+        &SelfVesting {
+            amount,
+            start: env.block.time,
+            cliff,
+            duration,
+        },
+    )?;
+    Ok(Response::new().add_event(
+        Event::new("set_self_vesting")
+            .add_attribute("shares_subject", info.sender)
+            .add_attribute("amount", amount)
+            .add_attribute("start", env.block.time.to_string())
+            .add_attribute("cliff", cliff.to_string())
+            .add_attribute("duration", duration.to_string()),
+    ))
+}
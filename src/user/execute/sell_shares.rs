@@ -1,133 +1,188 @@
 use crate::{
-    state::{SHARES_BALANCE, SHARES_HOLDERS, SHARES_SUPPLY, STATE},
-    util::{calculate_fee, get_price},
+    state::{State, SHARES_BALANCE, SHARES_HOLDERS, SHARES_SUPPLY, STATE},
+    util::{
+        calculate_fee, check_circuit_breaker, distribute_holder_rewards, ensure_circuit_breaker,
+        ensure_exit_not_blocked, ensure_reserves_migrated, ensure_transferable, get_price,
+        get_referral_fee_percent, get_market_status, get_subject_fee_percent, record_trade,
+        remove_reserve, settle_holder_rewards, split_protocol_fee, OUT_DENOM,
+    },
     ContractError,
 };
-use cosmwasm_std::{coins, Addr, BankMsg, Event, StdError, StdResult, Uint128};
-use cosmwasm_std::{DepsMut, MessageInfo, Response};
-
-const OUT_DENOM: &str = "inj";
+use cosmwasm_std::{coins, Addr, BankMsg, BlockInfo, Event, StdError, StdResult, Storage, Uint128};
+use cosmwasm_std::{DepsMut, Env, Response};
 
 const BASE_SUPPLY: Uint128 = Uint128::new(1);
 const TX_AMOUNT_SHARES: Uint128 = Uint128::new(1);
 
+// Result of a single share sell, `total` is part of `payouts`
+pub struct SellSettlement {
+    pub total: Uint128,
+    pub subject_fee: Uint128,
+    pub holder_reward: Uint128,
+    pub referral_fee: Uint128,
+    pub shares_balance_new: Uint128,
+    pub shares_supply_new: Uint128,
+    pub payouts: Vec<(Addr, Uint128)>,
+}
+
+// Debits one share from the holder, expects validated addresses
+pub fn apply_sell(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    state: &State,
+    holder: &Addr,
+    proceeds_recipient: &Addr,
+    shares_subject: &Addr,
+    referral: &Addr,
+) -> Result<SellSettlement, ContractError> {
+    let market_status = get_market_status(storage, shares_subject)?;
+    if !market_status.can_sell() {
+        return Err(ContractError::MarketSellsDisabled {
+            status: market_status.as_str().to_string(),
+        });
+    }
+    ensure_reserves_migrated(storage)?;
+    ensure_circuit_breaker(storage, block, shares_subject)?;
+    let shares_supply = BASE_SUPPLY
+        + SHARES_SUPPLY
+            .may_load(storage, shares_subject)?
+            .unwrap_or_default();
+    if shares_supply <= BASE_SUPPLY {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot sell the last share",
+        )));
+    }
+    let balance = SHARES_BALANCE
+        .may_load(storage, (holder, shares_subject))?
+        .unwrap_or_default();
+    if balance < BASE_SUPPLY {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Insufficient shares",
+        )));
+    }
+    ensure_transferable(storage, block, holder, shares_subject, balance, TX_AMOUNT_SHARES)?;
+    record_trade(storage, block, holder, shares_subject, false)?;
+
+    let price = get_price(shares_supply - TX_AMOUNT_SHARES);
+    let (_, _, subject_sell_fee_percent) = get_subject_fee_percent(storage, state, shares_subject)?;
+    let (_, _, referral_sell_fee_percent) = get_referral_fee_percent(storage, state, referral)?;
+
+    let protocol_fee = calculate_fee(price, state.protocol_sell_fee_percent);
+    let subject_fee = calculate_fee(price, subject_sell_fee_percent);
+    let referral_fee = calculate_fee(price, referral_sell_fee_percent);
+    let total = price - protocol_fee - subject_fee - referral_fee;
+
+    remove_reserve(storage, shares_subject, price)?;
+
+    let holder_reward = distribute_holder_rewards(storage, shares_subject, subject_fee)?;
+    let subject_fee = subject_fee - holder_reward;
+    settle_holder_rewards(storage, holder, shares_subject)?;
+
+    SHARES_BALANCE.update(
+        storage,
+        (holder, shares_subject),
+        block.height,
+        |balance: Option<Uint128>| -> StdResult<_> {
+            Ok(balance.unwrap_or_default() - TX_AMOUNT_SHARES)
+        },
+    )?;
+
+    SHARES_SUPPLY.update(
+        storage,
+        shares_subject,
+        block.height,
+        |supply: Option<Uint128>| -> StdResult<_> {
+            Ok(supply.unwrap_or_default() - TX_AMOUNT_SHARES)
+        },
+    )?;
+
+    if balance == BASE_SUPPLY {
+        SHARES_HOLDERS.update(
+            storage,
+            shares_subject,
+            |holders: Option<Uint128>| -> StdResult<_> {
+                Ok(holders.unwrap_or_default() - TX_AMOUNT_SHARES)
+            },
+        )?;
+    }
+
+    let mut payouts = vec![(proceeds_recipient.clone(), total)];
+    payouts.extend(split_protocol_fee(&state.protocol_fee_recipients, protocol_fee));
+    payouts.push((shares_subject.clone(), subject_fee));
+    payouts.push((referral.clone(), referral_fee));
+
+    Ok(SellSettlement {
+        total,
+        subject_fee,
+        holder_reward,
+        referral_fee,
+        shares_balance_new: balance - TX_AMOUNT_SHARES,
+        shares_supply_new: shares_supply - TX_AMOUNT_SHARES,
+        payouts,
+    })
+}
+
 pub fn sell_shares(
     deps: DepsMut,
-    info: MessageInfo,
+    env: Env,
+    holder: Addr,
+    proceeds_recipient: Addr,
     shares_subject: Addr,
     referral: Addr,
 ) -> Result<Response, ContractError> {
     let validated_referral_address = deps.api.addr_validate(&referral.to_string())?;
     let validated_shares_subject_address = deps.api.addr_validate(&shares_subject.to_string())?;
+    ensure_exit_not_blocked(
+        deps.as_ref(),
+        &[
+            &holder,
+            &proceeds_recipient,
+            &validated_shares_subject_address,
+            &validated_referral_address,
+        ],
+    )?;
+
     let state = STATE.load(deps.storage)?;
-    let shares_supply = BASE_SUPPLY
-        + SHARES_SUPPLY
-            .may_load(deps.storage, &validated_shares_subject_address)?
-            .unwrap_or_default();
-    let shares_balance = SHARES_BALANCE
-        .may_load(
-            deps.storage,
-            (&info.sender, &validated_shares_subject_address),
-        )?
-        .unwrap_or_default();
-    if shares_supply > BASE_SUPPLY {
-        let price = get_price(shares_supply - TX_AMOUNT_SHARES);
-
-        let protocol_fee = calculate_fee(price, state.protocol_sell_fee_percent);
-        let subject_fee = calculate_fee(price, state.subject_sell_fee_percent);
-        let referral_fee = calculate_fee(price, state.referral_sell_fee_percent);
-        let total = price - protocol_fee - subject_fee - referral_fee;
-
-        let balance = SHARES_BALANCE
-            .may_load(
-                deps.storage,
-                (&info.sender, &validated_shares_subject_address),
-            )?
-            .unwrap_or_default();
+    let settlement = apply_sell(
+        deps.storage,
+        &env.block,
+        &state,
+        &holder,
+        &proceeds_recipient,
+        &validated_shares_subject_address,
+        &validated_referral_address,
+    )?;
+    let circuit_breaker_event =
+        check_circuit_breaker(deps.storage, &env.block, &validated_shares_subject_address)?;
+
+    let mut msgs: Vec<BankMsg> = Vec::new();
 
-        if balance >= BASE_SUPPLY {
-            SHARES_BALANCE.update(
-                deps.storage,
-                (&info.sender, &validated_shares_subject_address),
-                |balance: Option<Uint128>| -> StdResult<_> {
-                    Ok(balance.unwrap_or_default() - TX_AMOUNT_SHARES)
-                },
-            )?;
-
-            SHARES_SUPPLY.update(
-                deps.storage,
-                &validated_shares_subject_address,
-                |supply: Option<Uint128>| -> StdResult<_> {
-                    Ok(supply.unwrap_or_default() - TX_AMOUNT_SHARES)
-                },
-            )?;
-
-            if balance == BASE_SUPPLY {
-                SHARES_HOLDERS.update(
-                    deps.storage,
-                    &validated_shares_subject_address,
-                    |holders: Option<Uint128>| -> StdResult<_> {
-                        Ok(holders.unwrap_or_default() - TX_AMOUNT_SHARES)
-                    },
-                )?;
-            }
-
-            let mut msgs: Vec<BankMsg> = Vec::new();
-
-            if total > Uint128::zero() {
-                let funds_result = BankMsg::Send {
-                    to_address: info.sender.to_string(),
-                    amount: coins(total.into(), OUT_DENOM),
-                };
-                msgs.push(funds_result);
-            }
-
-            if protocol_fee > Uint128::zero() {
-                let protocol_fee_result = BankMsg::Send {
-                    to_address: state.protocol_fee_destination.to_string(),
-                    amount: coins(protocol_fee.into(), OUT_DENOM),
-                };
-                msgs.push(protocol_fee_result);
-            }
-
-            if subject_fee > Uint128::zero() {
-                let subject_fee_result = BankMsg::Send {
-                    to_address: validated_shares_subject_address.to_string(),
-                    amount: coins(subject_fee.into(), OUT_DENOM),
-                };
-                msgs.push(subject_fee_result);
-            }
-
-            if referral_fee > Uint128::zero() {
-                let referral_fee_result = BankMsg::Send {
-                    to_address: validated_referral_address.to_string(),
-                    amount: coins(referral_fee.into(), OUT_DENOM),
-                };
-                msgs.push(referral_fee_result);
-            }
-            let response = Response::new()
-                .add_event(
-                    Event::new("sell_shares")
-                        .add_attribute("sender", info.sender)
-                        .add_attribute("shares_subject", validated_shares_subject_address)
-                        .add_attribute("amount", TX_AMOUNT_SHARES)
-                        .add_attribute("shares_balance_new", shares_balance - TX_AMOUNT_SHARES)
-                        .add_attribute("shares_supply_new", shares_supply - TX_AMOUNT_SHARES)
-                        .add_attribute("subject_fees", subject_fee)
-                        .add_attribute("referral_fees", referral_fee)
-                        .add_attribute("referral", validated_referral_address)
-                        .add_attribute("total", total),
-                )
-                .add_messages(msgs);
-            return Ok(response);
-        } else {
-            Err(ContractError::Std(StdError::generic_err(
-                "Insufficient shares",
-            )))
+    for (payout_recipient, payout) in settlement.payouts {
+        if payout > Uint128::zero() {
+            let payout_result = BankMsg::Send {
+                to_address: payout_recipient.to_string(),
+                amount: coins(payout.into(), OUT_DENOM),
+            };
+            msgs.push(payout_result);
         }
-    } else {
-        Err(ContractError::Std(StdError::generic_err(
-            "Cannot sell the last share",
-        )))
     }
+
+    let response = Response::new()
+        .add_event(
+            Event::new("sell_shares")
+                .add_attribute("sender", holder)
+                .add_attribute("proceeds_recipient", proceeds_recipient)
+                .add_attribute("shares_subject", validated_shares_subject_address)
+                .add_attribute("amount", TX_AMOUNT_SHARES)
+                .add_attribute("shares_balance_new", settlement.shares_balance_new)
+                .add_attribute("shares_supply_new", settlement.shares_supply_new)
+                .add_attribute("subject_fees", settlement.subject_fee)
+                .add_attribute("holder_rewards", settlement.holder_reward)
+                .add_attribute("referral_fees", settlement.referral_fee)
+                .add_attribute("referral", validated_referral_address)
+                .add_attribute("total", settlement.total),
+        )
+        .add_events(circuit_breaker_event)
+        .add_messages(msgs);
+    return Ok(response);
 }
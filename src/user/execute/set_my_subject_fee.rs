@@ -0,0 +1,47 @@
+use cosmwasm_std::{DepsMut, Event, MessageInfo, Response, StdError, Storage, Uint128};
+
+use crate::{
+    state::{SubjectFee, SUBJECT_FEES},
+    util::get_subject_fee_bounds,
+    ContractError,
+};
+
+pub fn validate_my_subject_fee(
+    storage: &dyn Storage,
+    buy: Uint128,
+    sell: Uint128,
+) -> Result<(), ContractError> {
+    let bounds = get_subject_fee_bounds(storage)?;
+    for fee_percent in [buy, sell] {
+        if fee_percent < bounds.min_fee_percent || fee_percent > bounds.max_fee_percent {
+            return Err(ContractError::Std(StdError::generic_err(
+                "Subject fee is outside of the allowed bounds",
+            )));
+        }
+    }
+    Ok(())
+}
+
+// Applies to the sender's own market, runs through ProposeConfigChange
+pub fn set_my_subject_fee(
+    deps: DepsMut,
+    info: MessageInfo,
+    buy: Uint128,
+    sell: Uint128,
+) -> Result<Response, ContractError> {
+    validate_my_subject_fee(deps.storage, buy, sell)?;
+    SUBJECT_FEES.save(
+        deps.storage,
+        &info.sender,
+        &SubjectFee {
+            buy_fee_percent: buy,
+            sell_fee_percent: sell,
+        },
+    )?;
+    Ok(Response::new().add_event(
+        Event::new("set_my_subject_fee")
+            .add_attribute("shares_subject", info.sender)
+            .add_attribute("buy_fee_percent", buy)
+            .add_attribute("sell_fee_percent", sell),
+    ))
+}
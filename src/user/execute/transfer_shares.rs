@@ -0,0 +1,50 @@
+use cosmwasm_std::{Addr, DepsMut, Env, Event, MessageInfo, Response, StdError, Uint128};
+
+use crate::{
+    util::{ensure_not_blocked, move_shares, transfers_enabled},
+    ContractError,
+};
+
+pub fn transfer_shares(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    shares_subject: Addr,
+    recipient: Addr,
+    amount: Uint128,
+) -> Result<Response, ContractError> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    let validated_recipient_address = deps.api.addr_validate(recipient.as_str())?;
+
+    if !transfers_enabled(deps.storage, &validated_shares_subject_address)? {
+        return Err(ContractError::TransfersDisabled {});
+    }
+    ensure_not_blocked(deps.as_ref(), &[&info.sender, &validated_recipient_address])?;
+    if amount.is_zero() {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot transfer zero shares",
+        )));
+    }
+    if validated_recipient_address == info.sender {
+        return Err(ContractError::Std(StdError::generic_err(
+            "Cannot transfer shares to yourself",
+        )));
+    }
+
+    move_shares(
+        deps.storage,
+        &env.block,
+        &validated_shares_subject_address,
+        &info.sender,
+        &validated_recipient_address,
+        amount,
+    )?;
+
+    Ok(Response::new().add_event(
+        Event::new("transfer_shares")
+            .add_attribute("sender", info.sender)
+            .add_attribute("recipient", validated_recipient_address)
+            .add_attribute("shares_subject", validated_shares_subject_address)
+            .add_attribute("amount", amount),
+    ))
+}
@@ -0,0 +1,33 @@
+use cosmwasm_std::{Addr, Deps, Env, StdError, StdResult, Uint128};
+
+use crate::{
+    msg::AirdropClaimResponse,
+    state::{Airdrop, AIRDROPS, AIRDROP_CLAIMS},
+    user::execute::get_airdrop_share,
+};
+
+pub fn get_airdrop(deps: Deps, id: u64) -> StdResult<Airdrop> {
+    AIRDROPS
+        .may_load(deps.storage, id)?
+        .ok_or_else(|| StdError::generic_err("Airdrop not found"))
+}
+
+pub fn get_airdrop_claim(
+    deps: Deps,
+    env: Env,
+    id: u64,
+    holder: Addr,
+) -> StdResult<AirdropClaimResponse> {
+    let validated_holder_address = deps.api.addr_validate(holder.as_str())?;
+    let airdrop = get_airdrop(deps, id)?;
+    let claimable = if airdrop.expires.is_expired(&env.block) {
+        Uint128::zero()
+    } else {
+        get_airdrop_share(deps.storage, id, &airdrop, &validated_holder_address)?
+    };
+    let claimed = AIRDROP_CLAIMS.may_load(deps.storage, (id, &validated_holder_address))?;
+    Ok(AirdropClaimResponse {
+        claimable,
+        claimed: claimed.unwrap_or_default(),
+    })
+}
@@ -0,0 +1,33 @@
+use cosmwasm_std::{Addr, Deps, StdResult, Uint128};
+use cw_utils::Expiration;
+
+use crate::{msg::AllowanceResponse, state::ALLOWANCES};
+
+pub fn get_allowance(
+    deps: Deps,
+    owner: Addr,
+    spender: Addr,
+    shares_subject: Addr,
+) -> StdResult<AllowanceResponse> {
+    let validated_owner_address = deps.api.addr_validate(owner.as_str())?;
+    let validated_spender_address = deps.api.addr_validate(spender.as_str())?;
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    let allowance = ALLOWANCES.may_load(
+        deps.storage,
+        (
+            &validated_owner_address,
+            &validated_spender_address,
+            &validated_shares_subject_address,
+        ),
+    )?;
+    Ok(match allowance {
+        Some(allowance) => AllowanceResponse {
+            allowance: allowance.allowance,
+            expires: allowance.expires,
+        },
+        None => AllowanceResponse {
+            allowance: Uint128::zero(),
+            expires: Expiration::Never {},
+        },
+    })
+}
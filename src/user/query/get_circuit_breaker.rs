@@ -0,0 +1,21 @@
+use cosmwasm_std::{Addr, Deps, Env, StdResult};
+
+use crate::{msg::CircuitBreakerResponse, state::CIRCUIT_BREAKERS, util::get_circuit_breaker_config};
+
+pub fn get_circuit_breaker(
+    deps: Deps,
+    env: Env,
+    shares_subject: Addr,
+) -> StdResult<CircuitBreakerResponse> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    let breaker = CIRCUIT_BREAKERS.may_load(deps.storage, &validated_shares_subject_address)?;
+    let is_halted = breaker
+        .as_ref()
+        .and_then(|breaker| breaker.halted_until)
+        .is_some_and(|until_height| env.block.height < until_height);
+    Ok(CircuitBreakerResponse {
+        config: get_circuit_breaker_config(deps.storage)?,
+        breaker,
+        is_halted,
+    })
+}
@@ -0,0 +1,19 @@
+use cosmwasm_std::{Addr, Deps, StdResult};
+
+use crate::{msg::FeeScheduleResponse, state::STATE, util::get_subject_fee_percent};
+
+pub fn get_fee_schedule(deps: Deps, shares_subject: Addr) -> StdResult<FeeScheduleResponse> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    let state = STATE.load(deps.storage)?;
+    let (is_custom, subject_buy_fee_percent, subject_sell_fee_percent) =
+        get_subject_fee_percent(deps.storage, &state, &validated_shares_subject_address)?;
+    Ok(FeeScheduleResponse {
+        subject_buy_fee_percent,
+        subject_sell_fee_percent,
+        protocol_buy_fee_percent: state.protocol_buy_fee_percent,
+        protocol_sell_fee_percent: state.protocol_sell_fee_percent,
+        referral_buy_fee_percent: state.referral_buy_fee_percent,
+        referral_sell_fee_percent: state.referral_sell_fee_percent,
+        is_custom,
+    })
+}
@@ -0,0 +1,10 @@
+use cosmwasm_std::{Addr, Deps, StdResult};
+
+use crate::{msg::IsBlockedResponse, util::is_blocked};
+
+pub fn get_is_blocked(deps: Deps, address: Addr) -> StdResult<IsBlockedResponse> {
+    let validated_address = deps.api.addr_validate(address.as_str())?;
+    Ok(IsBlockedResponse {
+        is_blocked: is_blocked(deps, &validated_address)?,
+    })
+}
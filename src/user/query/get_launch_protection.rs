@@ -0,0 +1,24 @@
+use cosmwasm_std::{Addr, Deps, Env, StdResult};
+
+use crate::{msg::LaunchProtectionResponse, state::LAUNCH_PROTECTION};
+
+pub fn get_launch_protection(
+    deps: Deps,
+    env: Env,
+    shares_subject: Addr,
+) -> StdResult<LaunchProtectionResponse> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    let protection = LAUNCH_PROTECTION.may_load(deps.storage, &validated_shares_subject_address)?;
+    // Not launched yet counts as active, the window starts with the first buy
+    let is_active = match &protection {
+        Some(protection) => match protection.ends {
+            Some(ends) => !ends.is_expired(&env.block),
+            None => true,
+        },
+        None => false,
+    };
+    Ok(LaunchProtectionResponse {
+        protection,
+        is_active,
+    })
+}
@@ -0,0 +1,39 @@
+use cosmwasm_std::{Addr, Deps, Env, Order, StdResult, Uint128};
+
+use crate::{
+    msg::{LockInfo, LockedWeightResponse, LocksResponse},
+    state::{LOCKS, SUBJECT_LOCKS},
+};
+
+pub fn get_locks(deps: Deps, owner: Addr) -> StdResult<LocksResponse> {
+    let validated_owner_address = deps.api.addr_validate(owner.as_str())?;
+    let locks = LOCKS
+        .prefix(&validated_owner_address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(id, lock)| LockInfo { id, lock }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(LocksResponse { locks })
+}
+
+// Expired locks stop counting right away, whether or not their owner released them
+pub fn get_locked_weight(
+    deps: Deps,
+    env: Env,
+    shares_subject: Addr,
+) -> StdResult<LockedWeightResponse> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    let mut weight = Uint128::zero();
+    for item in SUBJECT_LOCKS.prefix(&validated_shares_subject_address).range(
+        deps.storage,
+        None,
+        None,
+        Order::Ascending,
+    ) {
+        let (id, owner) = item?;
+        let lock = LOCKS.load(deps.storage, (&owner, id))?;
+        if env.block.time < lock.unlocks_at {
+            weight += lock.weight;
+        }
+    }
+    Ok(LockedWeightResponse { weight })
+}
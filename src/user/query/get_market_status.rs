@@ -0,0 +1,11 @@
+use cosmwasm_std::{Addr, Deps, StdResult};
+
+use crate::{msg::MarketStatusResponse, util::{get_market_status, transfers_enabled}};
+
+pub fn get_market_status_query(deps: Deps, shares_subject: Addr) -> StdResult<MarketStatusResponse> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    Ok(MarketStatusResponse {
+        status: get_market_status(deps.storage, &validated_shares_subject_address)?,
+        transfers_enabled: transfers_enabled(deps.storage, &validated_shares_subject_address)?,
+    })
+}
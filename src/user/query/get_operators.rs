@@ -0,0 +1,20 @@
+use cosmwasm_std::{Addr, Deps, Env, Order, StdResult};
+
+use crate::{
+    msg::{OperatorInfo, OperatorsResponse},
+    state::OPERATORS,
+};
+
+pub fn get_operators(deps: Deps, env: Env, owner: Addr) -> StdResult<OperatorsResponse> {
+    let validated_owner_address = deps.api.addr_validate(owner.as_str())?;
+    let operators = OPERATORS
+        .prefix(&validated_owner_address)
+        .range(deps.storage, None, None, Order::Ascending)
+        .filter(|item| match item {
+            Ok((_, expires)) => !expires.is_expired(&env.block),
+            Err(_) => true,
+        })
+        .map(|item| item.map(|(operator, expires)| OperatorInfo { operator, expires }))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(OperatorsResponse { operators })
+}
@@ -0,0 +1,17 @@
+use cosmwasm_std::{Deps, Order, StdResult};
+
+use crate::{
+    msg::PendingConfigChangesResponse, state::PENDING_CONFIG_CHANGES,
+    util::get_config_change_delay,
+};
+
+pub fn get_pending_config_changes(deps: Deps) -> StdResult<PendingConfigChangesResponse> {
+    let changes = PENDING_CONFIG_CHANGES
+        .range(deps.storage, None, None, Order::Ascending)
+        .map(|item| item.map(|(_, change)| change))
+        .collect::<StdResult<Vec<_>>>()?;
+    Ok(PendingConfigChangesResponse {
+        delay: get_config_change_delay(deps.storage)?,
+        changes,
+    })
+}
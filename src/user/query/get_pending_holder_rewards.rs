@@ -0,0 +1,28 @@
+use cosmwasm_std::{Addr, Deps, StdResult};
+
+use crate::{
+    msg::PendingHolderRewardsResponse,
+    state::HOLDER_REWARD_PERCENT,
+    util::get_holder_rewards,
+};
+
+pub fn get_pending_holder_rewards(
+    deps: Deps,
+    shares_subject: Addr,
+    holder: Addr,
+) -> StdResult<PendingHolderRewardsResponse> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    let validated_holder_address = deps.api.addr_validate(holder.as_str())?;
+    let rewards = get_holder_rewards(
+        deps.storage,
+        &validated_holder_address,
+        &validated_shares_subject_address,
+    )?;
+    let reward_percent = HOLDER_REWARD_PERCENT
+        .may_load(deps.storage, &validated_shares_subject_address)?
+        .unwrap_or_default();
+    Ok(PendingHolderRewardsResponse {
+        pending: rewards.pending,
+        reward_percent,
+    })
+}
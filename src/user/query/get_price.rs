@@ -3,7 +3,7 @@ use cosmwasm_std::{Addr, Deps, StdResult, Uint128};
 use crate::{
     msg::GetPriceResponse,
     state::{SHARES_SUPPLY, STATE},
-    util::{calculate_fee, get_price},
+    util::{calculate_fee, get_price, get_referral_fee_percent, get_subject_fee_percent},
 };
 
 pub fn get_price_query(
@@ -11,6 +11,7 @@ pub fn get_price_query(
     shares_subject: Addr,
     with_fees: bool,
     is_buy: bool,
+    referral: Option<Addr>,
 ) -> StdResult<GetPriceResponse> {
     let validated_shares_subject_address = deps.api.addr_validate(&shares_subject.to_string())?;
     let state = STATE.load(deps.storage)?;
@@ -18,6 +19,17 @@ pub fn get_price_query(
         + SHARES_SUPPLY
             .may_load(deps.storage, &validated_shares_subject_address)?
             .unwrap_or_default();
+    let (_, subject_buy_fee_percent, subject_sell_fee_percent) =
+        get_subject_fee_percent(deps.storage, &state, &validated_shares_subject_address)?;
+    let (referral_buy_fee_percent, referral_sell_fee_percent) = match referral {
+        Some(referral) => {
+            let validated_referral_address = deps.api.addr_validate(referral.as_str())?;
+            let (_, buy_fee_percent, sell_fee_percent) =
+                get_referral_fee_percent(deps.storage, &state, &validated_referral_address)?;
+            (buy_fee_percent, sell_fee_percent)
+        }
+        None => (state.referral_buy_fee_percent, state.referral_sell_fee_percent),
+    };
 
     // Calculate the price without considering fees
     let base_price = get_price(if is_buy {
@@ -31,15 +43,15 @@ pub fn get_price_query(
         // BUY
         (
             calculate_fee(base_price, state.protocol_buy_fee_percent),
-            calculate_fee(base_price, state.subject_buy_fee_percent),
-            calculate_fee(base_price, state.referral_buy_fee_percent),
+            calculate_fee(base_price, subject_buy_fee_percent),
+            calculate_fee(base_price, referral_buy_fee_percent),
         )
     } else if with_fees && !is_buy {
         // SELL
         (
             calculate_fee(base_price, state.protocol_sell_fee_percent),
-            calculate_fee(base_price, state.subject_sell_fee_percent),
-            calculate_fee(base_price, state.referral_sell_fee_percent),
+            calculate_fee(base_price, subject_sell_fee_percent),
+            calculate_fee(base_price, referral_sell_fee_percent),
         )
     } else {
         // NO FEES
@@ -0,0 +1,10 @@
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::{msg::ProtocolFeeRecipientsResponse, state::STATE};
+
+pub fn get_protocol_fee_recipients(deps: Deps) -> StdResult<ProtocolFeeRecipientsResponse> {
+    let state = STATE.load(deps.storage)?;
+    Ok(ProtocolFeeRecipientsResponse {
+        recipients: state.protocol_fee_recipients,
+    })
+}
@@ -0,0 +1,23 @@
+use cosmwasm_std::{Addr, Deps, StdResult};
+
+use crate::{
+    msg::ReferralTierResponse,
+    state::{REFERRAL_VOLUME, STATE},
+    util::get_referral_fee_percent,
+};
+
+pub fn get_referral_tier(deps: Deps, referrer: Addr) -> StdResult<ReferralTierResponse> {
+    let validated_referrer_address = deps.api.addr_validate(referrer.as_str())?;
+    let state = STATE.load(deps.storage)?;
+    let volume = REFERRAL_VOLUME
+        .may_load(deps.storage, &validated_referrer_address)?
+        .unwrap_or_default();
+    let (tier, buy_fee_percent, sell_fee_percent) =
+        get_referral_fee_percent(deps.storage, &state, &validated_referrer_address)?;
+    Ok(ReferralTierResponse {
+        volume,
+        tier,
+        buy_fee_percent,
+        sell_fee_percent,
+    })
+}
@@ -0,0 +1,24 @@
+use cosmwasm_std::{Addr, Deps, Env, StdResult, Uint128};
+
+use crate::{msg::SelfVestingResponse, state::SELF_VESTING, util::get_vested_amount};
+
+pub fn get_self_vesting(
+    deps: Deps,
+    env: Env,
+    shares_subject: Addr,
+) -> StdResult<SelfVestingResponse> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    let vesting = SELF_VESTING.may_load(deps.storage, &validated_shares_subject_address)?;
+    let (vested, unvested) = match &vesting {
+        Some(vesting) => {
+            let vested = get_vested_amount(vesting, &env.block);
+            (vested, vesting.amount - vested)
+        }
+        None => (Uint128::zero(), Uint128::zero()),
+    };
+    Ok(SelfVestingResponse {
+        vesting,
+        vested,
+        unvested,
+    })
+}
@@ -0,0 +1,55 @@
+use cosmwasm_std::{Addr, Deps, Env, StdError, StdResult};
+
+use crate::{
+    msg::{BalanceAtHeightResponse, SupplyAtHeightResponse},
+    state::{SHARES_BALANCE, SHARES_SUPPLY, SNAPSHOT_START_HEIGHT},
+};
+
+// History only exists from SNAPSHOT_START_HEIGHT and future heights can still change
+fn ensure_snapshot_height(deps: Deps, env: &Env, height: u64) -> StdResult<()> {
+    let start_height = SNAPSHOT_START_HEIGHT.may_load(deps.storage)?.unwrap_or_default();
+    if height < start_height {
+        return Err(StdError::generic_err(format!(
+            "No snapshots before height {}",
+            start_height
+        )));
+    }
+    if height > env.block.height {
+        return Err(StdError::generic_err("Height is in the future"));
+    }
+    Ok(())
+}
+
+pub fn get_balance_at_height(
+    deps: Deps,
+    env: Env,
+    shares_subject: Addr,
+    address: Addr,
+    height: u64,
+) -> StdResult<BalanceAtHeightResponse> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    let validated_address = deps.api.addr_validate(address.as_str())?;
+    ensure_snapshot_height(deps, &env, height)?;
+    let balance = SHARES_BALANCE
+        .may_load_at_height(
+            deps.storage,
+            (&validated_address, &validated_shares_subject_address),
+            height,
+        )?
+        .unwrap_or_default();
+    Ok(BalanceAtHeightResponse { balance, height })
+}
+
+pub fn get_supply_at_height(
+    deps: Deps,
+    env: Env,
+    shares_subject: Addr,
+    height: u64,
+) -> StdResult<SupplyAtHeightResponse> {
+    let validated_shares_subject_address = deps.api.addr_validate(shares_subject.as_str())?;
+    ensure_snapshot_height(deps, &env, height)?;
+    let supply = SHARES_SUPPLY
+        .may_load_at_height(deps.storage, &validated_shares_subject_address, height)?
+        .unwrap_or_default();
+    Ok(SupplyAtHeightResponse { supply, height })
+}
@@ -0,0 +1,29 @@
+use cosmwasm_std::{Deps, Env, StdResult};
+
+use crate::{
+    msg::SolvencyResponse,
+    state::{AIRDROP_BALANCES, TOTAL_HOLDER_REWARDS, TOTAL_RESERVE},
+    util::{get_required_balance, OUT_DENOM},
+};
+
+pub fn get_solvency(deps: Deps, env: Env) -> StdResult<SolvencyResponse> {
+    let total_reserve = TOTAL_RESERVE.may_load(deps.storage)?.unwrap_or_default();
+    let total_holder_rewards = TOTAL_HOLDER_REWARDS.may_load(deps.storage)?.unwrap_or_default();
+    let total_airdrops = AIRDROP_BALANCES
+        .may_load(deps.storage, OUT_DENOM)?
+        .unwrap_or_default();
+    let required = get_required_balance(deps.storage, OUT_DENOM)?;
+    let balance = deps
+        .querier
+        .query_balance(env.contract.address, OUT_DENOM)?
+        .amount;
+    Ok(SolvencyResponse {
+        total_reserve,
+        total_holder_rewards,
+        total_airdrops,
+        balance,
+        surplus: balance.saturating_sub(required),
+        deficit: required.saturating_sub(balance),
+        is_solvent: balance >= required,
+    })
+}
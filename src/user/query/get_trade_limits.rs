@@ -0,0 +1,7 @@
+use cosmwasm_std::{Deps, StdResult};
+
+use crate::{state::TradeLimits, util};
+
+pub fn get_trade_limits(deps: Deps) -> StdResult<TradeLimits> {
+    util::get_trade_limits(deps.storage)
+}
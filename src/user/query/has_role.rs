@@ -0,0 +1,10 @@
+use cosmwasm_std::{Addr, Deps, StdResult};
+
+use crate::{msg::HasRoleResponse, state::Role, util::has_role};
+
+pub fn get_has_role(deps: Deps, role: Role, account: Addr) -> StdResult<HasRoleResponse> {
+    let validated_account = deps.api.addr_validate(account.as_str())?;
+    Ok(HasRoleResponse {
+        has_role: has_role(deps.storage, role, &validated_account)?,
+    })
+}
@@ -8,4 +8,58 @@ mod get_state;
 pub use get_state::get_state;
 
 mod get_subject_holders;
-pub use get_subject_holders::get_subject_holders;
\ No newline at end of file
+pub use get_subject_holders::get_subject_holders;
+
+mod get_referral_tier;
+pub use get_referral_tier::get_referral_tier;
+
+mod get_fee_schedule;
+pub use get_fee_schedule::get_fee_schedule;
+
+mod get_protocol_fee_recipients;
+pub use get_protocol_fee_recipients::get_protocol_fee_recipients;
+
+mod has_role;
+pub use has_role::get_has_role;
+
+mod get_pending_config_changes;
+pub use get_pending_config_changes::get_pending_config_changes;
+
+mod get_market_status;
+pub use get_market_status::get_market_status_query;
+
+mod get_solvency;
+pub use get_solvency::get_solvency;
+
+mod get_allowance;
+pub use get_allowance::get_allowance;
+
+mod get_operators;
+pub use get_operators::get_operators;
+
+mod get_launch_protection;
+pub use get_launch_protection::get_launch_protection;
+
+mod get_trade_limits;
+pub use get_trade_limits::get_trade_limits;
+
+mod get_circuit_breaker;
+pub use get_circuit_breaker::get_circuit_breaker;
+
+mod get_is_blocked;
+pub use get_is_blocked::get_is_blocked;
+
+mod get_snapshots;
+pub use get_snapshots::{get_balance_at_height, get_supply_at_height};
+
+mod get_pending_holder_rewards;
+pub use get_pending_holder_rewards::get_pending_holder_rewards;
+
+mod get_airdrop;
+pub use get_airdrop::{get_airdrop, get_airdrop_claim};
+
+mod get_locks;
+pub use get_locks::{get_locked_weight, get_locks};
+
+mod get_self_vesting;
+pub use get_self_vesting::get_self_vesting;
\ No newline at end of file
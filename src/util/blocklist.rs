@@ -0,0 +1,53 @@
+use cosmwasm_std::{Addr, Deps, StdResult};
+
+use crate::{
+    msg::{IsBlockedResponse, ScreeningQueryMsg},
+    state::{BLOCKED, SCREENING_CONTRACT},
+    ContractError,
+};
+
+// Checks the owner-managed blocklist first, then the screening contract if one is set
+pub fn is_blocked(deps: Deps, address: &Addr) -> StdResult<bool> {
+    if BLOCKED.has(deps.storage, address) {
+        return Ok(true);
+    }
+    match SCREENING_CONTRACT.may_load(deps.storage)? {
+        Some(screening_contract) => {
+            let response: IsBlockedResponse = deps.querier.query_wasm_smart(
+                screening_contract,
+                &ScreeningQueryMsg::IsBlocked {
+                    address: address.to_string(),
+                },
+            )?;
+            Ok(response.is_blocked)
+        }
+        None => Ok(false),
+    }
+}
+
+// Fails closed, a screening query that errors rejects the buy or transfer
+pub fn ensure_not_blocked(deps: Deps, addresses: &[&Addr]) -> Result<(), ContractError> {
+    for address in addresses {
+        let blocked =
+            is_blocked(deps, address).map_err(|_| ContractError::ScreeningUnavailable {})?;
+        if blocked {
+            return Err(ContractError::AddressBlocked {
+                address: address.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
+
+// Fails open for sells and claims, holders can always exit while the screening contract is down.
+// The owner-managed blocklist still applies since it never needs a query.
+pub fn ensure_exit_not_blocked(deps: Deps, addresses: &[&Addr]) -> Result<(), ContractError> {
+    for address in addresses {
+        if is_blocked(deps, address).unwrap_or(false) {
+            return Err(ContractError::AddressBlocked {
+                address: address.to_string(),
+            });
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,94 @@
+use cosmwasm_std::{Addr, BlockInfo, Event, StdResult, Storage, Uint128};
+
+use crate::{
+    state::{
+        CircuitBreaker, CircuitBreakerConfig, CIRCUIT_BREAKERS, CIRCUIT_BREAKER_CONFIG,
+        SHARES_SUPPLY,
+    },
+    util::{get_price, FEE_SCALE},
+    ContractError,
+};
+
+// Below this supply a single share moves the price by more than any sane threshold
+const CIRCUIT_BREAKER_MIN_SUPPLY: Uint128 = Uint128::new(10);
+
+pub fn get_circuit_breaker_config(storage: &dyn Storage) -> StdResult<CircuitBreakerConfig> {
+    Ok(CIRCUIT_BREAKER_CONFIG.may_load(storage)?.unwrap_or_default())
+}
+
+// Price of the next share, used as the circuit breaker reference
+fn get_spot_price(storage: &dyn Storage, shares_subject: &Addr) -> StdResult<Uint128> {
+    let supply = SHARES_SUPPLY.may_load(storage, shares_subject)?.unwrap_or_default();
+    Ok(get_price(supply + Uint128::new(1)))
+}
+
+// Runs before a trade, rejects halted subjects and starts a new reference window when due
+pub fn ensure_circuit_breaker(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    shares_subject: &Addr,
+) -> Result<(), ContractError> {
+    let breaker = CIRCUIT_BREAKERS.may_load(storage, shares_subject)?;
+    if let Some(until_height) = breaker.as_ref().and_then(|breaker| breaker.halted_until) {
+        if block.height < until_height {
+            return Err(ContractError::CircuitBreakerActive { until_height });
+        }
+    }
+    let config = get_circuit_breaker_config(storage)?;
+    let supply = SHARES_SUPPLY.may_load(storage, shares_subject)?.unwrap_or_default();
+    if config.max_price_move_percent.is_zero() || supply < CIRCUIT_BREAKER_MIN_SUPPLY {
+        return Ok(());
+    }
+    let window_ended = match &breaker {
+        Some(breaker) => {
+            breaker.halted_until.is_some()
+                || block.height >= breaker.window_start + config.window_blocks
+        }
+        None => true,
+    };
+    if window_ended {
+        let breaker = CircuitBreaker {
+            reference_price: get_spot_price(storage, shares_subject)?,
+            window_start: block.height,
+            halted_until: None,
+        };
+        CIRCUIT_BREAKERS.save(storage, shares_subject, &breaker)?;
+    }
+    Ok(())
+}
+
+// Runs once per subject after all trades of a message, halts the subject when the price
+// moved too far from the reference. Checking per share would fail the rest of a batch.
+pub fn check_circuit_breaker(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    shares_subject: &Addr,
+) -> Result<Option<Event>, ContractError> {
+    let config = get_circuit_breaker_config(storage)?;
+    if config.max_price_move_percent.is_zero() {
+        return Ok(None);
+    }
+    let mut breaker = match CIRCUIT_BREAKERS.may_load(storage, shares_subject)? {
+        Some(breaker) if !breaker.reference_price.is_zero() => breaker,
+        _ => return Ok(None),
+    };
+    let price = get_spot_price(storage, shares_subject)?;
+    let price_move = if price > breaker.reference_price {
+        price - breaker.reference_price
+    } else {
+        breaker.reference_price - price
+    };
+    if price_move * FEE_SCALE <= breaker.reference_price * config.max_price_move_percent {
+        return Ok(None);
+    }
+    let until_height = block.height + config.cooldown_blocks;
+    breaker.halted_until = Some(until_height);
+    CIRCUIT_BREAKERS.save(storage, shares_subject, &breaker)?;
+    Ok(Some(
+        Event::new("circuit_breaker_tripped")
+            .add_attribute("shares_subject", shares_subject)
+            .add_attribute("reference_price", breaker.reference_price)
+            .add_attribute("price", price)
+            .add_attribute("halted_until", until_height.to_string()),
+    ))
+}
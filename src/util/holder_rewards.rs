@@ -0,0 +1,66 @@
+use cosmwasm_std::{Addr, Decimal256, StdResult, Storage, Uint128};
+
+use crate::{
+    state::{
+        HolderRewards, HOLDER_REWARDS, HOLDER_REWARD_INDEX, HOLDER_REWARD_PERCENT, SHARES_BALANCE,
+        SHARES_SUPPLY, TOTAL_HOLDER_REWARDS,
+    },
+    util::calculate_fee,
+};
+
+// Rewards of a holder accrued up to the current subject index, without storing them
+pub fn get_holder_rewards(
+    storage: &dyn Storage,
+    holder: &Addr,
+    shares_subject: &Addr,
+) -> StdResult<HolderRewards> {
+    let index = HOLDER_REWARD_INDEX.may_load(storage, shares_subject)?.unwrap_or_default();
+    let mut rewards = HOLDER_REWARDS
+        .may_load(storage, (holder, shares_subject))?
+        .unwrap_or_default();
+    let balance = SHARES_BALANCE
+        .may_load(storage, (holder, shares_subject))?
+        .unwrap_or_default();
+    let accrued =
+        (Decimal256::from_ratio(balance, 1u128) * (index - rewards.index)).to_uint_floor();
+    rewards.pending += Uint128::try_from(accrued)?;
+    rewards.index = index;
+    Ok(rewards)
+}
+
+// Has to run before every balance change of the holder
+pub fn settle_holder_rewards(
+    storage: &mut dyn Storage,
+    holder: &Addr,
+    shares_subject: &Addr,
+) -> StdResult<HolderRewards> {
+    let rewards = get_holder_rewards(storage, holder, shares_subject)?;
+    HOLDER_REWARDS.save(storage, (holder, shares_subject), &rewards)?;
+    Ok(rewards)
+}
+
+// Moves the holder part of a subject fee into the reward index, split over the supply
+// before the trade. Returns the part taken from the subject fee.
+pub fn distribute_holder_rewards(
+    storage: &mut dyn Storage,
+    shares_subject: &Addr,
+    subject_fee: Uint128,
+) -> StdResult<Uint128> {
+    let reward_percent = HOLDER_REWARD_PERCENT
+        .may_load(storage, shares_subject)?
+        .unwrap_or_default();
+    let amount = calculate_fee(subject_fee, reward_percent);
+    let supply = SHARES_SUPPLY.may_load(storage, shares_subject)?.unwrap_or_default();
+    if amount.is_zero() || supply.is_zero() {
+        return Ok(Uint128::zero());
+    }
+    let index = HOLDER_REWARD_INDEX.may_load(storage, shares_subject)?.unwrap_or_default();
+    HOLDER_REWARD_INDEX.save(
+        storage,
+        shares_subject,
+        &(index + Decimal256::from_ratio(amount, supply)),
+    )?;
+    let total_holder_rewards = TOTAL_HOLDER_REWARDS.may_load(storage)?.unwrap_or_default();
+    TOTAL_HOLDER_REWARDS.save(storage, &(total_holder_rewards + amount))?;
+    Ok(amount)
+}
@@ -0,0 +1,64 @@
+use cosmwasm_std::{Addr, BlockInfo, Storage, Uint128};
+
+use crate::{
+    state::{LaunchPurchases, LAUNCH_ALLOWLIST, LAUNCH_PROTECTION, LAUNCH_PURCHASES},
+    ContractError,
+};
+
+// Starts the launch window on the first buy, then applies its rules until it ends.
+// The allowlist covers both payer and recipient, the cap and interval count against the payer.
+// The subject itself is never restricted in its own market.
+pub fn check_launch_protection(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    shares_subject: &Addr,
+    payer: &Addr,
+    recipient: &Addr,
+) -> Result<(), ContractError> {
+    let mut protection = match LAUNCH_PROTECTION.may_load(storage, shares_subject)? {
+        Some(protection) => protection,
+        None => return Ok(()),
+    };
+    let ends = match protection.ends {
+        Some(ends) => ends,
+        None => {
+            let ends = protection.window.after(block);
+            protection.ends = Some(ends);
+            LAUNCH_PROTECTION.save(storage, shares_subject, &protection)?;
+            ends
+        }
+    };
+    if ends.is_expired(block) {
+        return Ok(());
+    }
+
+    if protection.allowlist_only {
+        for address in [payer, recipient] {
+            if address != shares_subject
+                && !LAUNCH_ALLOWLIST.has(storage, (shares_subject, address))
+            {
+                return Err(ContractError::LaunchNotAllowlisted {});
+            }
+        }
+    }
+    if payer == shares_subject {
+        return Ok(());
+    }
+    let mut purchases: LaunchPurchases = LAUNCH_PURCHASES
+        .may_load(storage, (shares_subject, payer))?
+        .unwrap_or_default();
+    if let Some(max_shares) = protection.max_shares_per_address {
+        if purchases.count >= max_shares {
+            return Err(ContractError::LaunchCapReached {});
+        }
+    }
+    if let Some(next_buy) = purchases.next_buy {
+        if !next_buy.is_expired(block) {
+            return Err(ContractError::LaunchBuyTooSoon {});
+        }
+    }
+    purchases.count += Uint128::new(1);
+    purchases.next_buy = protection.min_buy_interval.map(|interval| interval.after(block));
+    LAUNCH_PURCHASES.save(storage, (shares_subject, payer), &purchases)?;
+    Ok(())
+}
@@ -0,0 +1,311 @@
+use std::str::FromStr;
+
+use cosmwasm_std::{Addr, BlockInfo, Decimal, StdError, StdResult, Storage, Uint128};
+use cw_utils::Expiration;
+
+use crate::{
+    state::{
+        FeeRecipient, MarketStatus, Role, ShareAllowance, State, SubjectFeeBounds, AIRDROP_BALANCES,
+        ALLOWANCES, CONFIG_CHANGE_DELAY, MARKET_STATUS, OPERATORS, REFERRAL_TIERS, REFERRAL_VOLUME,
+        RESERVE, RESERVE_MIGRATION, ROLES, SHARES_BALANCE, SHARES_HOLDERS, SUBJECT_FEES,
+        SUBJECT_FEE_BOUNDS, TOTAL_HOLDER_REWARDS, TOTAL_RESERVE, TRANSFERS_DISABLED,
+    },
+    ContractError,
+};
+
+mod launch_protection;
+pub use launch_protection::check_launch_protection;
+
+mod trade_limits;
+pub use trade_limits::{get_trade_limits, record_trade};
+
+mod circuit_breaker;
+pub use circuit_breaker::{
+    check_circuit_breaker, ensure_circuit_breaker, get_circuit_breaker_config,
+};
+
+mod blocklist;
+pub use blocklist::{ensure_exit_not_blocked, ensure_not_blocked, is_blocked};
+
+mod holder_rewards;
+pub use holder_rewards::{distribute_holder_rewards, get_holder_rewards, settle_holder_rewards};
+
+mod vesting;
+pub use vesting::{ensure_transferable, get_unvested_amount, get_vested_amount};
+
+// Denom of the bonding curve, every price, fee and payout is in it
+pub const OUT_DENOM: &str = "inj";
+
+pub const FEE_SCALE: Uint128 = Uint128::new(100000);
+
+// Protocol fee recipient weights are in basis points
+pub const TOTAL_FEE_WEIGHT: u64 = 10_000;
+
+// Upper bound of the subject fee, same as the owner setter allows
+pub const MAX_SUBJECT_FEE_PERCENT: Uint128 = Uint128::new(5000);
+
+// Default timelock delay for config changes (1 day)
+pub const DEFAULT_CONFIG_CHANGE_DELAY: u64 = 86400;
+
+const MULTIPLY_SCALER: f64 = 0.1;
+const FRACTION: f64 = 0.06;
+const FRACTION_DENOM: f64 = 7.8;
+const FORMULA_EXPONENT: f64 = 2.05;
+
+pub fn calculate_fee(price: Uint128, fee_percent: Uint128) -> Uint128 {
+    return price * fee_percent / FEE_SCALE;
+}
+
+pub fn get_price(supply: Uint128) -> Uint128 {
+    if supply.is_zero() {
+        return Uint128::zero();
+    }
+    let price2 = MULTIPLY_SCALER * (FRACTION + supply.u128() as f64 / FRACTION_DENOM).powf(FORMULA_EXPONENT);
+    let price_decimal = Decimal::from_str(&price2.to_string()).unwrap();
+    let price = price_decimal.atomics();
+    return price;
+}
+
+// Returns the index of the highest tier the referrer has reached together with its
+// (buy, sell) fee percent. Falls back to the global referral fees below the first tier.
+pub fn get_referral_fee_percent(
+    storage: &dyn Storage,
+    state: &State,
+    referral: &Addr,
+) -> StdResult<(Option<u32>, Uint128, Uint128)> {
+    let tiers = REFERRAL_TIERS.may_load(storage)?.unwrap_or_default();
+    let volume = REFERRAL_VOLUME.may_load(storage, referral)?.unwrap_or_default();
+    let reached = tiers.iter().rposition(|tier| volume >= tier.min_volume);
+    match reached {
+        Some(index) => Ok((
+            Some(index as u32),
+            tiers[index].buy_fee_percent,
+            tiers[index].sell_fee_percent,
+        )),
+        None => Ok((
+            None,
+            state.referral_buy_fee_percent,
+            state.referral_sell_fee_percent,
+        )),
+    }
+}
+
+// Only buys count, and only when the referral is neither the payer nor the subject
+pub fn add_referral_volume(
+    storage: &mut dyn Storage,
+    referral: &Addr,
+    payer: &Addr,
+    shares_subject: &Addr,
+    volume: Uint128,
+) -> StdResult<()> {
+    if referral == payer || referral == shares_subject {
+        return Ok(());
+    }
+    REFERRAL_VOLUME.update(storage, referral, |total: Option<Uint128>| -> StdResult<_> {
+        Ok(total.unwrap_or_default() + volume)
+    })?;
+    Ok(())
+}
+
+pub fn get_subject_fee_bounds(storage: &dyn Storage) -> StdResult<SubjectFeeBounds> {
+    Ok(SUBJECT_FEE_BOUNDS.may_load(storage)?.unwrap_or(SubjectFeeBounds {
+        min_fee_percent: Uint128::zero(),
+        max_fee_percent: MAX_SUBJECT_FEE_PERCENT,
+    }))
+}
+
+// Returns whether the subject set its own fees together with the (buy, sell) fee percent.
+// Overrides are clamped so later changes of the bounds apply to existing markets.
+pub fn get_subject_fee_percent(
+    storage: &dyn Storage,
+    state: &State,
+    shares_subject: &Addr,
+) -> StdResult<(bool, Uint128, Uint128)> {
+    match SUBJECT_FEES.may_load(storage, shares_subject)? {
+        Some(fee) => {
+            let bounds = get_subject_fee_bounds(storage)?;
+            Ok((
+                true,
+                fee.buy_fee_percent.clamp(bounds.min_fee_percent, bounds.max_fee_percent),
+                fee.sell_fee_percent.clamp(bounds.min_fee_percent, bounds.max_fee_percent),
+            ))
+        }
+        None => Ok((
+            false,
+            state.subject_buy_fee_percent,
+            state.subject_sell_fee_percent,
+        )),
+    }
+}
+
+// Splits the protocol fee by recipient weight, the rounding dust goes to the last recipient
+pub fn split_protocol_fee(recipients: &[FeeRecipient], protocol_fee: Uint128) -> Vec<(Addr, Uint128)> {
+    let mut remaining = protocol_fee;
+    let mut shares = Vec::with_capacity(recipients.len());
+    for (index, recipient) in recipients.iter().enumerate() {
+        let share = if index == recipients.len() - 1 {
+            remaining
+        } else {
+            protocol_fee.multiply_ratio(recipient.weight, TOTAL_FEE_WEIGHT)
+        };
+        remaining -= share;
+        shares.push((recipient.address.clone(), share));
+    }
+    shares
+}
+
+pub fn has_role(storage: &dyn Storage, role: Role, account: &Addr) -> StdResult<bool> {
+    Ok(ROLES.may_load(storage, (role.as_str(), account))?.unwrap_or_default())
+}
+
+pub fn ensure_role(storage: &dyn Storage, role: Role, account: &Addr) -> Result<(), ContractError> {
+    if !has_role(storage, role, account)? {
+        return Err(ContractError::Unauthorized {});
+    }
+    Ok(())
+}
+
+pub fn get_config_change_delay(storage: &dyn Storage) -> StdResult<u64> {
+    Ok(CONFIG_CHANGE_DELAY.may_load(storage)?.unwrap_or(DEFAULT_CONFIG_CHANGE_DELAY))
+}
+
+pub fn get_market_status(storage: &dyn Storage, shares_subject: &Addr) -> StdResult<MarketStatus> {
+    Ok(MARKET_STATUS.may_load(storage, shares_subject)?.unwrap_or_default())
+}
+
+// Reserves are only consistent once migrate has rebuilt all of them
+pub fn ensure_reserves_migrated(storage: &dyn Storage) -> Result<(), ContractError> {
+    if RESERVE_MIGRATION.exists(storage) {
+        return Err(ContractError::ReserveMigrationPending {});
+    }
+    Ok(())
+}
+
+pub fn add_reserve(storage: &mut dyn Storage, shares_subject: &Addr, amount: Uint128) -> StdResult<()> {
+    RESERVE.update(storage, shares_subject, |reserve: Option<Uint128>| -> StdResult<_> {
+        Ok(reserve.unwrap_or_default() + amount)
+    })?;
+    let total_reserve = TOTAL_RESERVE.may_load(storage)?.unwrap_or_default();
+    TOTAL_RESERVE.save(storage, &(total_reserve + amount))
+}
+
+pub fn remove_reserve(
+    storage: &mut dyn Storage,
+    shares_subject: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let reserve = RESERVE.may_load(storage, shares_subject)?.unwrap_or_default();
+    let total_reserve = TOTAL_RESERVE.may_load(storage)?.unwrap_or_default();
+    if reserve < amount || total_reserve < amount {
+        return Err(ContractError::InsufficientReserve {});
+    }
+    RESERVE.save(storage, shares_subject, &(reserve - amount))?;
+    TOTAL_RESERVE.save(storage, &(total_reserve - amount))?;
+    Ok(())
+}
+
+// Funds of a denom the contract owes to holders and cannot hand out otherwise
+pub fn get_required_balance(storage: &dyn Storage, denom: &str) -> StdResult<Uint128> {
+    let airdrop_balance = AIRDROP_BALANCES.may_load(storage, denom)?.unwrap_or_default();
+    if denom != OUT_DENOM {
+        return Ok(airdrop_balance);
+    }
+    let total_reserve = TOTAL_RESERVE.may_load(storage)?.unwrap_or_default();
+    let total_holder_rewards = TOTAL_HOLDER_REWARDS.may_load(storage)?.unwrap_or_default();
+    Ok(total_reserve + total_holder_rewards + airdrop_balance)
+}
+
+pub fn transfers_enabled(storage: &dyn Storage, shares_subject: &Addr) -> StdResult<bool> {
+    Ok(!TRANSFERS_DISABLED.may_load(storage, shares_subject)?.unwrap_or_default())
+}
+
+// Moves shares between holders outside of the curve, keeping the holder count in sync
+pub fn move_shares(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    shares_subject: &Addr,
+    from: &Addr,
+    to: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let from_balance = SHARES_BALANCE
+        .may_load(storage, (from, shares_subject))?
+        .unwrap_or_default();
+    if from_balance < amount {
+        return Err(ContractError::Std(StdError::generic_err("Insufficient shares")));
+    }
+    ensure_transferable(storage, block, from, shares_subject, from_balance, amount)?;
+    let to_balance = SHARES_BALANCE
+        .may_load(storage, (to, shares_subject))?
+        .unwrap_or_default();
+    settle_holder_rewards(storage, from, shares_subject)?;
+    settle_holder_rewards(storage, to, shares_subject)?;
+    SHARES_BALANCE.save(storage, (from, shares_subject), &(from_balance - amount), block.height)?;
+    SHARES_BALANCE.save(storage, (to, shares_subject), &(to_balance + amount), block.height)?;
+
+    let mut holders = SHARES_HOLDERS.may_load(storage, shares_subject)?.unwrap_or_default();
+    if from_balance == amount {
+        holders -= Uint128::one();
+    }
+    if to_balance.is_zero() {
+        holders += Uint128::one();
+    }
+    SHARES_HOLDERS.save(storage, shares_subject, &holders)?;
+    Ok(())
+}
+
+pub fn spend_allowance(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    owner: &Addr,
+    spender: &Addr,
+    shares_subject: &Addr,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let key = (owner, spender, shares_subject);
+    let allowance: ShareAllowance = ALLOWANCES
+        .may_load(storage, key)?
+        .ok_or(ContractError::InsufficientAllowance {})?;
+    if allowance.expires.is_expired(block) {
+        return Err(ContractError::AllowanceExpired {});
+    }
+    if allowance.allowance < amount {
+        return Err(ContractError::InsufficientAllowance {});
+    }
+    let remaining = allowance.allowance - amount;
+    if remaining.is_zero() {
+        ALLOWANCES.remove(storage, key);
+    } else {
+        ALLOWANCES.save(
+            storage,
+            key,
+            &ShareAllowance {
+                allowance: remaining,
+                expires: allowance.expires,
+            },
+        )?;
+    }
+    Ok(())
+}
+
+pub fn ensure_operator(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    owner: &Addr,
+    operator: &Addr,
+) -> Result<(), ContractError> {
+    match OPERATORS.may_load(storage, (owner, operator))? {
+        Some(expires) if !expires.is_expired(block) => Ok(()),
+        _ => Err(ContractError::OperatorNotApproved {}),
+    }
+}
+
+pub fn ensure_deadline(
+    block: &BlockInfo,
+    deadline: Option<Expiration>,
+) -> Result<(), ContractError> {
+    match deadline {
+        Some(deadline) if deadline.is_expired(block) => Err(ContractError::DeadlineExceeded {}),
+        _ => Ok(()),
+    }
+}
\ No newline at end of file
@@ -0,0 +1,57 @@
+use cosmwasm_std::{Addr, BlockInfo, StdResult, Storage};
+
+use crate::{
+    state::{LastTrade, TradeLimits, LAST_TRADES, TRADE_LIMITS},
+    ContractError,
+};
+
+pub fn get_trade_limits(storage: &dyn Storage) -> StdResult<TradeLimits> {
+    Ok(TRADE_LIMITS.may_load(storage)?.unwrap_or_default())
+}
+
+// Enforces the buy/sell cooldown and the per-block trade cap for one holder and subject
+pub fn record_trade(
+    storage: &mut dyn Storage,
+    block: &BlockInfo,
+    holder: &Addr,
+    shares_subject: &Addr,
+    is_buy: bool,
+) -> Result<(), ContractError> {
+    let limits = get_trade_limits(storage)?;
+    if limits.buy_sell_cooldown_blocks == 0 && limits.max_trades_per_block == 0 {
+        return Ok(());
+    }
+    let mut last_trade: LastTrade = LAST_TRADES
+        .may_load(storage, (holder, shares_subject))?
+        .unwrap_or_default();
+
+    let opposite_height = if is_buy {
+        last_trade.last_sell_height
+    } else {
+        last_trade.last_buy_height
+    };
+    if let Some(opposite_height) = opposite_height {
+        let ready_height = opposite_height + limits.buy_sell_cooldown_blocks;
+        if block.height < ready_height {
+            return Err(ContractError::TradeCooldown {
+                blocks_remaining: ready_height - block.height,
+            });
+        }
+    }
+
+    if last_trade.block_height != block.height {
+        last_trade.block_height = block.height;
+        last_trade.block_trades = 0;
+    }
+    if limits.max_trades_per_block > 0 && last_trade.block_trades >= limits.max_trades_per_block {
+        return Err(ContractError::TradeRateLimited {});
+    }
+    last_trade.block_trades += 1;
+    if is_buy {
+        last_trade.last_buy_height = Some(block.height);
+    } else {
+        last_trade.last_sell_height = Some(block.height);
+    }
+    LAST_TRADES.save(storage, (holder, shares_subject), &last_trade)?;
+    Ok(())
+}
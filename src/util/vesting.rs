@@ -0,0 +1,60 @@
+use cosmwasm_std::{Addr, BlockInfo, StdResult, Storage, Uint128};
+
+use crate::{
+    state::{SelfVesting, LOCKED_SHARES, SELF_VESTING},
+    ContractError,
+};
+
+pub fn get_vested_amount(vesting: &SelfVesting, block: &BlockInfo) -> Uint128 {
+    let elapsed = block.time.seconds().saturating_sub(vesting.start.seconds());
+    if elapsed < vesting.cliff {
+        return Uint128::zero();
+    }
+    if elapsed >= vesting.duration {
+        return vesting.amount;
+    }
+    vesting.amount.multiply_ratio(elapsed, vesting.duration)
+}
+
+// Only subjects selling or moving their own shares have unvested shares
+pub fn get_unvested_amount(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    holder: &Addr,
+    shares_subject: &Addr,
+) -> StdResult<Uint128> {
+    if holder != shares_subject {
+        return Ok(Uint128::zero());
+    }
+    Ok(match SELF_VESTING.may_load(storage, shares_subject)? {
+        Some(vesting) => vesting.amount - get_vested_amount(&vesting, block),
+        None => Uint128::zero(),
+    })
+}
+
+// Shares leaving a holder have to be unlocked and vested. A lock can cover unvested
+// shares, so only the larger of the two is held back.
+pub fn ensure_transferable(
+    storage: &dyn Storage,
+    block: &BlockInfo,
+    holder: &Addr,
+    shares_subject: &Addr,
+    balance: Uint128,
+    amount: Uint128,
+) -> Result<(), ContractError> {
+    let unvested = get_unvested_amount(storage, block, holder, shares_subject)?;
+    let locked = LOCKED_SHARES
+        .may_load(storage, (holder, shares_subject))?
+        .unwrap_or_default();
+    if balance.saturating_sub(locked.max(unvested)) >= amount {
+        return Ok(());
+    }
+    if unvested > locked {
+        return Err(ContractError::SharesNotVested {
+            unvested: unvested.to_string(),
+        });
+    }
+    Err(ContractError::SharesLocked {
+        locked: locked.to_string(),
+    })
+}
@@ -1,13 +1,72 @@
 #[cfg(test)]
 mod tests {
-    use cosmwasm_std::testing::{mock_dependencies, mock_env, mock_info};
-    use cosmwasm_std::{coins, from_json, Addr, Uint128};
-    use rust_contract::contract::{execute, instantiate, query};
+    use cosmwasm_std::testing::{
+        mock_dependencies, mock_env, mock_info, MockApi, MockQuerier, MockStorage,
+        MOCK_CONTRACT_ADDR,
+    };
+    use cosmwasm_std::{
+        coins, from_json, to_json_binary, Addr, BankMsg, Coin, ContractResult, CosmosMsg,
+        MessageInfo, OwnedDeps, Response, SubMsg, SystemResult, Uint128, WasmQuery,
+    };
+    use rust_contract::contract::{execute, instantiate, migrate, query};
+    use cw_utils::{Duration, Expiration};
     use rust_contract::msg::{
-        ExecuteMsg, GetPriceResponse, GetShareBalanceResponse, InstantiateMsg, QueryMsg,
+        AirdropClaimResponse, AllowanceResponse, BalanceAtHeightResponse, CircuitBreakerResponse,
+        ConfigChange, ExecuteMsg, FeeScheduleResponse, GetPriceResponse, GetShareBalanceResponse,
+        GetSubjectHoldersResponse, HasRoleResponse, InstantiateMsg, IsBlockedResponse,
+        LaunchProtectionResponse, LockedWeightResponse, LocksResponse, MarketStatusResponse,
+        MigrateMsg, OperatorsResponse, PendingConfigChangesResponse, PendingHolderRewardsResponse,
+        PollResponse, PollStatus, PollVoteResponse, ProtocolFeeRecipientsResponse, QueryMsg,
+        ReferralTierResponse, ScreeningQueryMsg, SelfVestingResponse, SolvencyResponse,
+        SupplyAtHeightResponse, TradeOp,
     };
-    use rust_contract::state::State;
+    use rust_contract::state::{
+        FeeRecipient, MarketStatus, ReferralTier, Role, State, TradeLimits, TradingMode, RESERVE,
+    };
+    use cw_storage_plus::Map;
+    use rust_contract::ContractError;
     const INSTANTIATE_MSG: InstantiateMsg = InstantiateMsg {};
+    const CONFIG_CHANGE_DELAY: u64 = 86400;
+
+    // Proposes a config change and executes it once the timelock delay has passed
+    fn apply_config_change(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        info: MessageInfo,
+        change: ConfigChange,
+    ) -> Result<Response, ContractError> {
+        let msg = ExecuteMsg::ProposeConfigChange { change };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg)?;
+        let id = res.events[0]
+            .attributes
+            .iter()
+            .find(|attr| attr.key == "id")
+            .unwrap()
+            .value
+            .parse()
+            .unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(CONFIG_CHANGE_DELAY);
+        execute(deps.as_mut(), env, info, ExecuteMsg::ExecuteConfigChange { id })
+    }
+
+    // Buys one share of `subject` referred by the subject itself, `blocks` after mock_env
+    fn buy(
+        deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+        buyer: &str,
+        subject: &str,
+        blocks: u64,
+    ) -> Result<Response, ContractError> {
+        let mut env = mock_env();
+        env.block.height += blocks;
+        let info = mock_info(buyer, &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked(subject),
+            referral: Addr::unchecked(subject),
+            recipient: None,
+            deadline: None,
+        };
+        execute(deps.as_mut(), env, info, msg)
+    }
     #[test]
     fn proper_initialization() {
         let mut deps = mock_dependencies();
@@ -30,8 +89,11 @@ mod tests {
                 protocol_sell_fee_percent: Uint128::new(3000),
                 referral_buy_fee_percent: Uint128::new(500),
                 referral_sell_fee_percent: Uint128::new(0),
-                protocol_fee_destination: Addr::unchecked("creator"),
-                trading_is_enabled: true,
+                protocol_fee_recipients: vec![FeeRecipient {
+                    address: Addr::unchecked("creator"),
+                    weight: 10000,
+                }],
+                trading_mode: TradingMode::Open,
             },
             state
         );
@@ -48,65 +110,2133 @@ mod tests {
         assert_eq!(0, res.messages.len());
 
         let info = mock_info("creator", &coins(1000, "inj"));
-        let msg = ExecuteMsg::SetFeeDestination {
+        let msg = ConfigChange::SetFeeDestination {
             fee_destination: Addr::unchecked("fee_destination"),
         };
 
         // we can just call .unwrap() to assert this was a success
-        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = apply_config_change(&mut deps, info, msg).unwrap();
         assert_eq!(0, res.messages.len());
 
         // it worked, let's query the state
         let res = query(deps.as_ref(), mock_env(), QueryMsg::GetState {}).unwrap();
         let state: State = from_json(&res).unwrap();
+        assert_eq!(1, state.protocol_fee_recipients.len());
         assert_eq!(
             "fee_destination",
-            state.protocol_fee_destination.to_string()
+            state.protocol_fee_recipients[0].address.to_string()
         );
     }
 
     #[test]
-    fn set_protocol_fee_percent() {
+    fn referral_tiers() {
         let mut deps = mock_dependencies();
 
+        // init
         let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
 
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
-        assert_eq!(0, res.messages.len());
+        // set tiers
+        let info = mock_info("creator", &[]);
+        let msg = ConfigChange::SetReferralTiers {
+            tiers: vec![
+                ReferralTier {
+                    min_volume: Uint128::zero(),
+                    buy_fee_percent: Uint128::new(500),
+                    sell_fee_percent: Uint128::new(0),
+                },
+                ReferralTier {
+                    min_volume: Uint128::new(1),
+                    buy_fee_percent: Uint128::new(1000),
+                    sell_fee_percent: Uint128::new(100),
+                },
+            ],
+        };
+        apply_config_change(&mut deps, info, msg).unwrap();
+
+        let msg = QueryMsg::ReferralTier {
+            referrer: Addr::unchecked("referrer"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg.clone()).unwrap();
+        let tier: ReferralTierResponse = from_json(&res).unwrap();
+        assert_eq!(Some(0), tier.tier);
+        assert_eq!(Uint128::new(500), tier.buy_fee_percent);
+
+        // buy first share with referrer
+        let info = mock_info("anyone", &coins(1000000000000000000, "inj"));
+        let msg_buy = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("anyone"),
+            referral: Addr::unchecked("referrer"),
+            recipient: None,
+            deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg_buy).unwrap();
+
+        // referrer moved up a tier
+        let res = query(deps.as_ref(), mock_env(), msg.clone()).unwrap();
+        let tier: ReferralTierResponse = from_json(&res).unwrap();
+        assert!(tier.volume > Uint128::zero());
+        assert_eq!(Some(1), tier.tier);
+        assert_eq!(Uint128::new(1000), tier.buy_fee_percent);
+        assert_eq!(Uint128::new(100), tier.sell_fee_percent);
+
+        // self-referrals and sells add no volume
+        let volume = tier.volume;
+        buy(&mut deps, "anyone", "anyone", 0).unwrap();
+        let info = mock_info("referrer", &coins(1000000000000000000, "inj"));
+        let msg_buy = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("anyone"),
+            referral: Addr::unchecked("referrer"),
+            recipient: None,
+            deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg_buy).unwrap();
+        let info = mock_info("anyone", &[]);
+        let msg_sell = ExecuteMsg::SellShares {
+            shares_subject: Addr::unchecked("anyone"),
+            referral: Addr::unchecked("referrer"),
+            proceeds_recipient: None,
+            deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg_sell).unwrap();
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let tier: ReferralTierResponse = from_json(&res).unwrap();
+        assert_eq!(volume, tier.volume);
+
+        // quotes and buys charge the tier fee, paying the exact quote is enough
+        let quote = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, with_fees: bool| {
+            let msg = QueryMsg::GetPrice {
+                shares_subject: Addr::unchecked("anyone"),
+                with_fees,
+                is_buy: true,
+                referral: Some(Addr::unchecked("referrer")),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_json::<GetPriceResponse>(&res).unwrap().price
+        };
+        let base_price = quote(&deps, false);
+        let info = mock_info("buyer", &coins(quote(&deps, true).u128(), "inj"));
+        let msg_buy = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("anyone"),
+            referral: Addr::unchecked("referrer"),
+            recipient: None,
+            deadline: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg_buy).unwrap();
+        assert!(res.messages.contains(&SubMsg::new(BankMsg::Send {
+            to_address: "referrer".to_string(),
+            amount: coins((base_price * Uint128::new(1000) / Uint128::new(100000)).u128(), "inj"),
+        })));
+        // protocol, subject and referral fees, nothing left to refund
+        assert_eq!(3, res.messages.len());
+
+        // tiers must be sorted
+        let info = mock_info("creator", &[]);
+        let msg = ConfigChange::SetReferralTiers {
+            tiers: vec![
+                ReferralTier {
+                    min_volume: Uint128::new(10),
+                    buy_fee_percent: Uint128::new(500),
+                    sell_fee_percent: Uint128::new(0),
+                },
+                ReferralTier {
+                    min_volume: Uint128::new(1),
+                    buy_fee_percent: Uint128::new(1000),
+                    sell_fee_percent: Uint128::new(0),
+                },
+            ],
+        };
+        assert!(apply_config_change(&mut deps, info, msg).is_err());
+    }
+
+    #[test]
+    fn set_my_subject_fee() {
+        let mut deps = mock_dependencies();
 
+        // init
         let info = mock_info("creator", &coins(1000, "inj"));
-        let msg = ExecuteMsg::SetProtocolBuyFeePercent {
-            protocol_buy_fee_percent: Uint128::new(10),
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+
+        // owner limits subject fees
+        let info = mock_info("creator", &[]);
+        let msg = ConfigChange::SetSubjectFeeBounds {
+            min_fee_percent: Uint128::new(1000),
+            max_fee_percent: Uint128::new(4000),
         };
+        apply_config_change(&mut deps, info, msg).unwrap();
 
-        // we can just call .unwrap() to assert this was a success
+        // subject cannot go above the maximum
+        let info = mock_info("subject", &[]);
+        let msg = ConfigChange::SetMySubjectFee {
+            buy: Uint128::new(4500),
+            sell: Uint128::new(2000),
+        };
+        assert!(apply_config_change(&mut deps, info, msg).is_err());
+
+        // the change waits for the timelock and only the subject can apply it
+        let info = mock_info("subject", &[]);
+        let msg = ExecuteMsg::ProposeConfigChange {
+            change: ConfigChange::SetMySubjectFee {
+                buy: Uint128::new(4000),
+                sell: Uint128::new(1000),
+            },
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("subject", &[]);
+        let msg = ExecuteMsg::ExecuteConfigChange { id: 2 };
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::ConfigChangeNotReady { .. }));
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(CONFIG_CHANGE_DELAY);
+        let info = mock_info("creator", &[]);
+        let err = execute(deps.as_mut(), env.clone(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let info = mock_info("subject", &[]);
+        execute(deps.as_mut(), env, info, msg).unwrap();
+
+        let msg = QueryMsg::FeeSchedule {
+            shares_subject: Addr::unchecked("subject"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let schedule: FeeScheduleResponse = from_json(&res).unwrap();
+        assert!(schedule.is_custom);
+        assert_eq!(Uint128::new(4000), schedule.subject_buy_fee_percent);
+        assert_eq!(Uint128::new(1000), schedule.subject_sell_fee_percent);
+
+        // other subjects keep the global fees
+        let msg = QueryMsg::FeeSchedule {
+            shares_subject: Addr::unchecked("anyone"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let schedule: FeeScheduleResponse = from_json(&res).unwrap();
+        assert!(!schedule.is_custom);
+        assert_eq!(Uint128::new(3000), schedule.subject_buy_fee_percent);
+
+        // buy price with fees uses the subject fee
+        let info = mock_info("subject", &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            recipient: None,
+            deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let price_query = |with_fees: bool| -> Uint128 {
+            let msg = QueryMsg::GetPrice {
+                shares_subject: Addr::unchecked("subject"),
+                with_fees,
+                is_buy: true,
+                referral: None,
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let price: GetPriceResponse = from_json(&res).unwrap();
+            price.price
+        };
+        let base_price = price_query(false);
+        let expected = base_price
+            + base_price * Uint128::new(2500) / Uint128::new(100000)
+            + base_price * Uint128::new(4000) / Uint128::new(100000)
+            + base_price * Uint128::new(500) / Uint128::new(100000);
+        assert_eq!(expected, price_query(true));
+    }
+
+    #[test]
+    fn set_protocol_fee_recipients() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+
+        // weights must sum to 10000
+        let info = mock_info("creator", &[]);
+        let msg = ConfigChange::SetProtocolFeeRecipients {
+            recipients: vec![
+                FeeRecipient {
+                    address: Addr::unchecked("treasury"),
+                    weight: 5000,
+                },
+                FeeRecipient {
+                    address: Addr::unchecked("insurance"),
+                    weight: 3000,
+                },
+            ],
+        };
+        assert!(apply_config_change(&mut deps, info, msg).is_err());
+
+        // weights that would wrap around to 10000 are rejected too
+        let info = mock_info("creator", &[]);
+        let msg = ConfigChange::SetProtocolFeeRecipients {
+            recipients: vec![
+                FeeRecipient {
+                    address: Addr::unchecked("treasury"),
+                    weight: u64::MAX,
+                },
+                FeeRecipient {
+                    address: Addr::unchecked("insurance"),
+                    weight: 10001,
+                },
+            ],
+        };
+        assert!(apply_config_change(&mut deps, info, msg).is_err());
+
+        let recipients = vec![
+            FeeRecipient {
+                address: Addr::unchecked("treasury"),
+                weight: 5000,
+            },
+            FeeRecipient {
+                address: Addr::unchecked("insurance"),
+                weight: 3000,
+            },
+            FeeRecipient {
+                address: Addr::unchecked("dev"),
+                weight: 2000,
+            },
+        ];
+        let info = mock_info("creator", &[]);
+        let msg = ConfigChange::SetProtocolFeeRecipients {
+            recipients: recipients.clone(),
+        };
+        apply_config_change(&mut deps, info, msg).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ProtocolFeeRecipients {}).unwrap();
+        let response: ProtocolFeeRecipientsResponse = from_json(&res).unwrap();
+        assert_eq!(recipients, response.recipients);
+
+        // a buy pays every recipient and the split adds up to the protocol fee
+        let info = mock_info("anyone", &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("anyone"),
+            referral: Addr::unchecked("anyone"),
+            recipient: None,
+            deadline: None,
+        };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
-        assert_eq!(0, res.messages.len());
+        let paid = |recipient: &str| -> Uint128 {
+            res.messages
+                .iter()
+                .filter_map(|msg| match &msg.msg {
+                    CosmosMsg::Bank(BankMsg::Send { to_address, amount }) if to_address == recipient => {
+                        Some(amount[0].amount)
+                    }
+                    _ => None,
+                })
+                .sum()
+        };
+        let treasury = paid("treasury");
+        let insurance = paid("insurance");
+        let dev = paid("dev");
+        assert!(treasury > insurance && insurance > dev && dev > Uint128::zero());
 
-        // it worked, let's query the state
+        let msg = QueryMsg::GetPrice {
+            shares_subject: Addr::unchecked("anyone"),
+            with_fees: false,
+            is_buy: false,
+            referral: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let price: GetPriceResponse = from_json(&res).unwrap();
+        let protocol_fee = price.price * Uint128::new(2500) / Uint128::new(100000);
+        assert_eq!(protocol_fee, treasury + insurance + dev);
+    }
+
+    #[test]
+    fn migrate_legacy_state() {
+        let mut deps = mock_dependencies();
+
+        // state as stored before the protocol fee recipients list
+        cw2::set_contract_version(deps.as_mut().storage, "crates.io:my-first-contract", "0.0.1").unwrap();
+        deps.as_mut().storage.set(
+            b"state",
+            br#"{"owner":"creator","subject_buy_fee_percent":"3000","subject_sell_fee_percent":"3000","protocol_buy_fee_percent":"2500","protocol_sell_fee_percent":"3000","referral_buy_fee_percent":"500","referral_sell_fee_percent":"0","protocol_fee_destination":"treasury","trading_is_enabled":true}"#,
+        );
+        // supplies were a plain map before snapshots
+        let supplies = Map::<&Addr, Uint128>::new("shares_supply");
+        supplies
+            .save(deps.as_mut().storage, &Addr::unchecked("subject"), &Uint128::new(2))
+            .unwrap();
+        supplies
+            .save(deps.as_mut().storage, &Addr::unchecked("whale"), &Uint128::new(2500))
+            .unwrap();
+
+        // the reserve rebuild runs in batches, trades wait for the last one
+        let reserves_migrated = |res: &Response| {
+            res.events[0]
+                .attributes
+                .iter()
+                .find(|attr| attr.key == "reserves_migrated")
+                .unwrap()
+                .value
+                .clone()
+        };
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!("false", reserves_migrated(&res));
+        let err = buy(&mut deps, "trader", "subject", 0).unwrap_err();
+        assert!(matches!(err, ContractError::ReserveMigrationPending {}));
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!("false", reserves_migrated(&res));
+        let res = migrate(deps.as_mut(), mock_env(), MigrateMsg {}).unwrap();
+        assert_eq!("true", reserves_migrated(&res));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::ProtocolFeeRecipients {}).unwrap();
+        let response: ProtocolFeeRecipientsResponse = from_json(&res).unwrap();
+        assert_eq!(
+            vec![FeeRecipient {
+                address: Addr::unchecked("treasury"),
+                weight: 10000,
+            }],
+            response.recipients
+        );
         let res = query(deps.as_ref(), mock_env(), QueryMsg::GetState {}).unwrap();
         let state: State = from_json(&res).unwrap();
-        assert_eq!(Uint128::new(10), state.protocol_buy_fee_percent);
+        assert_eq!(TradingMode::Open, state.trading_mode);
+
+        // the reserve is rebuilt from the outstanding supply
+        let reserve = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, subject: &str| {
+            RESERVE.load(deps.as_ref().storage, &Addr::unchecked(subject)).unwrap()
+        };
+        assert_eq!(Uint128::new(12710106205131929), reserve(&deps, "subject"));
+        assert_eq!(Uint128::new(11249572491850380982165215), reserve(&deps, "whale"));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+        let solvency: SolvencyResponse = from_json(&res).unwrap();
+        assert_eq!(Uint128::new(11249572504560487187297144), solvency.total_reserve);
+        buy(&mut deps, "trader", "subject", 0).unwrap();
+
+        // existing supplies stay readable through the snapshots
+        let msg = QueryMsg::SupplyAtHeight {
+            shares_subject: Addr::unchecked("subject"),
+            height: mock_env().block.height,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let response: SupplyAtHeightResponse = from_json(&res).unwrap();
+        assert_eq!(Uint128::new(2), response.supply);
+
+        // the owner keeps every permission it had before
+        let msg = QueryMsg::HasRole {
+            role: Role::Admin,
+            account: Addr::unchecked("creator"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let response: HasRoleResponse = from_json(&res).unwrap();
+        assert!(response.has_role);
     }
 
     #[test]
-    fn set_subject_fee_percent() {
+    fn role_based_access() {
         let mut deps = mock_dependencies();
 
+        // init, the creator holds every role
         let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let has_role = |deps: &cosmwasm_std::OwnedDeps<_, _, _>, role: Role, account: &str| -> bool {
+            let msg = QueryMsg::HasRole {
+                role,
+                account: Addr::unchecked(account),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let response: HasRoleResponse = from_json(&res).unwrap();
+            response.has_role
+        };
+        assert!(has_role(&deps, Role::Admin, "creator"));
+        assert!(has_role(&deps, Role::Pauser, "creator"));
+        assert!(has_role(&deps, Role::FeeManager, "creator"));
 
-        // we can just call .unwrap() to assert this was a success
-        let res = instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
-        assert_eq!(0, res.messages.len());
+        // only an admin can grant roles
+        let info = mock_info("pauser", &[]);
+        let msg = ExecuteMsg::GrantRole {
+            role: Role::Pauser,
+            account: Addr::unchecked("pauser"),
+        };
+        assert!(execute(deps.as_mut(), mock_env(), info, msg.clone()).is_err());
+        let info = mock_info("creator", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(has_role(&deps, Role::Pauser, "pauser"));
+
+        // the pauser can toggle trading but cannot change fees
+        let info = mock_info("pauser", &[]);
+        let msg = ConfigChange::SetProtocolBuyFeePercent {
+            protocol_buy_fee_percent: Uint128::new(10),
+        };
+        assert!(apply_config_change(&mut deps, info, msg).is_err());
+        let info = mock_info("pauser", &[]);
+        let msg = ExecuteMsg::SetTradingMode {
+            mode: TradingMode::Halted,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // renouncing drops the role
+        let info = mock_info("pauser", &[]);
+        let msg = ExecuteMsg::RenounceRole { role: Role::Pauser };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(!has_role(&deps, Role::Pauser, "pauser"));
+
+        // the admin can revoke roles, even its own fee manager role
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::RevokeRole {
+            role: Role::FeeManager,
+            account: Addr::unchecked("creator"),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let msg = ConfigChange::SetProtocolBuyFeePercent {
+            protocol_buy_fee_percent: Uint128::new(10),
+        };
+        assert!(apply_config_change(&mut deps, info, msg).is_err());
+
+        // the last admin can neither renounce nor be revoked
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::RenounceRole { role: Role::Admin };
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::RevokeRole {
+            role: Role::Admin,
+            account: Addr::unchecked("creator"),
+        };
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::GrantRole {
+            role: Role::Admin,
+            account: Addr::unchecked("admin"),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::RenounceRole { role: Role::Admin };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert!(!has_role(&deps, Role::Admin, "creator"));
+    }
+
+    #[test]
+    fn timelocked_config_change() {
+        let mut deps = mock_dependencies();
 
+        // init
         let info = mock_info("creator", &coins(1000, "inj"));
-        let msg = ExecuteMsg::SetSubjectBuyFeePercent {
-            subject_buy_fee_percent: Uint128::new(10),
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+
+        // propose a fee change
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ProposeConfigChange {
+            change: ConfigChange::SetProtocolBuyFeePercent {
+                protocol_buy_fee_percent: Uint128::new(5000),
+            },
         };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
-        // we can just call .unwrap() to assert this was a success
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::PendingConfigChanges {}).unwrap();
+        let pending: PendingConfigChangesResponse = from_json(&res).unwrap();
+        assert_eq!(CONFIG_CHANGE_DELAY, pending.delay);
+        assert_eq!(1, pending.changes.len());
+        assert_eq!(
+            mock_env().block.time.plus_seconds(CONFIG_CHANGE_DELAY),
+            pending.changes[0].eta
+        );
+
+        // cannot execute before the eta
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ExecuteConfigChange { id: 1 };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::ConfigChangeNotReady { .. }));
+
+        // cancel drops the change
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::CancelConfigChange { id: 1 };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(CONFIG_CHANGE_DELAY);
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ExecuteConfigChange { id: 1 };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::ConfigChangeNotFound {}));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetState {}).unwrap();
+        let state: State = from_json(&res).unwrap();
+        assert_eq!(Uint128::new(2500), state.protocol_buy_fee_percent);
+
+        // invalid payloads are rejected when proposed
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ProposeConfigChange {
+            change: ConfigChange::SetProtocolBuyFeePercent {
+                protocol_buy_fee_percent: Uint128::new(5001),
+            },
+        };
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ProposeConfigChange {
+            change: ConfigChange::SetProtocolFeeRecipients {
+                recipients: vec![FeeRecipient {
+                    address: Addr::unchecked("treasury"),
+                    weight: 9999,
+                }],
+            },
+        };
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+
+        // a ready change expires once the grace period has passed
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ProposeConfigChange {
+            change: ConfigChange::SetProtocolBuyFeePercent {
+                protocol_buy_fee_percent: Uint128::new(5000),
+            },
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let mut env = mock_env();
+        env.block.time = env.block.time.plus_seconds(CONFIG_CHANGE_DELAY + 14 * 86400 + 1);
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::ExecuteConfigChange { id: 2 };
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::ConfigChangeExpired { .. }));
+
+        // the timelock delay itself is the admin's
+        let info = mock_info("fee_manager", &[]);
+        let grant = ExecuteMsg::GrantRole {
+            role: Role::FeeManager,
+            account: info.sender.clone(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), grant).unwrap();
+        let msg = ExecuteMsg::ProposeConfigChange {
+            change: ConfigChange::SetConfigChangeDelay { delay: 0 },
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let info = mock_info("creator", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // emergency pause is not delayed
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::SetTradingMode {
+            mode: TradingMode::Halted,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn market_status() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::GrantRole {
+            role: Role::Moderator,
+            account: Addr::unchecked("moderator"),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let sell = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+            let info = mock_info("anyone", &[]);
+            let msg = ExecuteMsg::SellShares {
+                shares_subject: Addr::unchecked("anyone"),
+                referral: Addr::unchecked("anyone"),
+                proceeds_recipient: None,
+                deadline: None,
+            };
+            execute(deps.as_mut(), mock_env(), info, msg)
+        };
+        let set_status = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, sender: &str, status| {
+            let info = mock_info(sender, &[]);
+            let msg = ExecuteMsg::SetMarketStatus {
+                shares_subject: Addr::unchecked("anyone"),
+                status,
+            };
+            execute(deps.as_mut(), mock_env(), info, msg)
+        };
+        buy(&mut deps, "anyone", "anyone", 0).unwrap();
+        buy(&mut deps, "anyone", "anyone", 0).unwrap();
+        buy(&mut deps, "anyone", "anyone", 0).unwrap();
+
+        // random accounts cannot moderate
+        assert!(set_status(&mut deps, "anyone", MarketStatus::Paused).is_err());
+
+        // paused market rejects both sides
+        set_status(&mut deps, "moderator", MarketStatus::Paused).unwrap();
+        let err = buy(&mut deps, "anyone", "anyone", 0).unwrap_err();
+        assert!(matches!(err, ContractError::MarketBuysDisabled { .. }));
+        assert!(matches!(sell(&mut deps).unwrap_err(), ContractError::MarketSellsDisabled { .. }));
+
+        // buy paused market still allows sells
+        set_status(&mut deps, "moderator", MarketStatus::BuyPaused).unwrap();
+        assert!(buy(&mut deps, "anyone", "anyone", 0).is_err());
+        sell(&mut deps).unwrap();
+
+        // delisted market winds down with sells only and cannot be reactivated
+        set_status(&mut deps, "creator", MarketStatus::Delisted).unwrap();
+        assert!(buy(&mut deps, "anyone", "anyone", 0).is_err());
+        sell(&mut deps).unwrap();
+        let err = set_status(&mut deps, "creator", MarketStatus::Active).unwrap_err();
+        assert!(matches!(err, ContractError::MarketDelisted {}));
+
+        let msg = QueryMsg::MarketStatus {
+            shares_subject: Addr::unchecked("anyone"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let response: MarketStatusResponse = from_json(&res).unwrap();
+        assert_eq!(MarketStatus::Delisted, response.status);
+    }
+
+    #[test]
+    fn sell_only_mode() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        for _ in 0..2 {
+            let info = mock_info("anyone", &coins(1000000000000000000, "inj"));
+            let msg = ExecuteMsg::BuyShares {
+                shares_subject: Addr::unchecked("anyone"),
+                referral: Addr::unchecked("anyone"),
+                recipient: None,
+                deadline: None,
+            };
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        let info = mock_info("creator", &[]);
+        let msg = ExecuteMsg::SetTradingMode {
+            mode: TradingMode::SellOnly,
+        };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!("set_trading_mode", res.events[0].ty);
+
+        // buys are rejected, sells go through
+        let info = mock_info("anyone", &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("anyone"),
+            referral: Addr::unchecked("anyone"),
+            recipient: None,
+            deadline: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::TradingIsSellOnly {}));
+        let info = mock_info("anyone", &[]);
+        let msg = ExecuteMsg::SellShares {
+            shares_subject: Addr::unchecked("anyone"),
+            referral: Addr::unchecked("anyone"),
+            proceeds_recipient: None,
+            deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+
+        // halted rejects sells too
+        let info = mock_info("creator", &[]);
+        let msg_halt = ExecuteMsg::SetTradingMode {
+            mode: TradingMode::Halted,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg_halt).unwrap();
+        let info = mock_info("anyone", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::TradingIsDisabled {}));
+    }
+
+    #[test]
+    fn reserve_and_solvency() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+
+        // every buy adds its curve price to the reserve
+        let mut expected_reserve = Uint128::zero();
+        for _ in 0..3 {
+            let msg = QueryMsg::GetPrice {
+                shares_subject: Addr::unchecked("anyone"),
+                with_fees: false,
+                is_buy: true,
+                referral: None,
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let price: GetPriceResponse = from_json(&res).unwrap();
+            expected_reserve += price.price;
+
+            let info = mock_info("anyone", &coins(1000000000000000000, "inj"));
+            let msg = ExecuteMsg::BuyShares {
+                shares_subject: Addr::unchecked("anyone"),
+                referral: Addr::unchecked("anyone"),
+                recipient: None,
+                deadline: None,
+            };
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // a sell takes its curve price out of the reserve
+        let msg = QueryMsg::GetPrice {
+            shares_subject: Addr::unchecked("anyone"),
+            with_fees: false,
+            is_buy: false,
+            referral: None,
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let price: GetPriceResponse = from_json(&res).unwrap();
+        expected_reserve -= price.price;
+        let info = mock_info("anyone", &[]);
+        let msg_sell = ExecuteMsg::SellShares {
+            shares_subject: Addr::unchecked("anyone"),
+            referral: Addr::unchecked("anyone"),
+            proceeds_recipient: None,
+            deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg_sell.clone()).unwrap();
+
+        // the contract holds exactly the reserve
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(expected_reserve.u128(), "inj"));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+        let solvency: SolvencyResponse = from_json(&res).unwrap();
+        assert_eq!(expected_reserve, solvency.total_reserve);
+        assert!(solvency.is_solvent);
+        assert_eq!(Uint128::zero(), solvency.surplus);
+
+        // leaked funds show up as a deficit
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(expected_reserve.u128() - 1, "inj"));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+        let solvency: SolvencyResponse = from_json(&res).unwrap();
+        assert!(!solvency.is_solvent);
+        assert_eq!(Uint128::one(), solvency.deficit);
+
+        // sells cannot take the subject reserve below zero
+        RESERVE
+            .save(deps.as_mut().storage, &Addr::unchecked("anyone"), &Uint128::one())
+            .unwrap();
+        let info = mock_info("anyone", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg_sell).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientReserve {}));
+    }
+
+    #[test]
+    fn sweep_surplus() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        for _ in 0..3 {
+            let info = mock_info("anyone", &coins(1000000000000000000, "inj"));
+            let msg = ExecuteMsg::BuyShares {
+                shares_subject: Addr::unchecked("anyone"),
+                referral: Addr::unchecked("anyone"),
+                recipient: None,
+                deadline: None,
+            };
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+        let reserve = from_json::<SolvencyResponse>(&res).unwrap().total_reserve;
+
+        // dust on top of the reserve plus a token sent by mistake
+        deps.querier.update_balance(
+            MOCK_CONTRACT_ADDR,
+            vec![
+                Coin::new(reserve.u128() + 777, "inj"),
+                Coin::new(5, "usdt"),
+            ],
+        );
+
+        // only the admin can sweep
+        let msg = ExecuteMsg::SweepSurplus {
+            denom: "inj".to_string(),
+            recipient: Addr::unchecked("treasury"),
+        };
+        let info = mock_info("anyone", &[]);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg.clone()).is_err());
+
+        let info = mock_info("creator", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(777, "inj"),
+            }),
+            res.messages[0].msg
+        );
+
+        // other denoms are not backing any shares
+        let info = mock_info("creator", &[]);
+        let msg_usdt = ExecuteMsg::SweepSurplus {
+            denom: "usdt".to_string(),
+            recipient: Addr::unchecked("treasury"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg_usdt).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "treasury".to_string(),
+                amount: coins(5, "usdt"),
+            }),
+            res.messages[0].msg
+        );
+
+        // once swept only the reserve is left and it cannot be touched
+        deps.querier
+            .update_balance(MOCK_CONTRACT_ADDR, coins(reserve.u128(), "inj"));
+        let info = mock_info("creator", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoSurplus {}));
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+        let solvency: SolvencyResponse = from_json(&res).unwrap();
+        assert_eq!(reserve, solvency.total_reserve);
+        assert!(solvency.is_solvent);
+    }
+
+    #[test]
+    fn transfer_shares() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        for _ in 0..2 {
+            let info = mock_info("subject", &coins(1000000000000000000, "inj"));
+            let msg = ExecuteMsg::BuyShares {
+                shares_subject: Addr::unchecked("subject"),
+                referral: Addr::unchecked("subject"),
+                recipient: None,
+                deadline: None,
+            };
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+        let balance = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, holder: &str| {
+            let msg = QueryMsg::GetShareBalance {
+                shares_subject: Addr::unchecked("subject"),
+                my_address: Addr::unchecked(holder),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_json::<GetShareBalanceResponse>(&res).unwrap().amount
+        };
+        let holders = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>| {
+            let msg = QueryMsg::GetSubjectHolders {
+                shares_subject: Addr::unchecked("subject"),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_json::<GetSubjectHoldersResponse>(&res).unwrap().amount
+        };
+
+        // gift one share, the recipient becomes a holder
+        let info = mock_info("subject", &[]);
+        let msg = ExecuteMsg::TransferShares {
+            shares_subject: Addr::unchecked("subject"),
+            recipient: Addr::unchecked("friend"),
+            amount: Uint128::new(1),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!("transfer_shares", res.events[0].ty);
+        assert_eq!(Uint128::new(1), balance(&deps, "subject"));
+        assert_eq!(Uint128::new(1), balance(&deps, "friend"));
+        assert_eq!(Uint128::new(2), holders(&deps));
+
+        // cannot send more than the balance
+        let info = mock_info("friend", &[]);
+        let msg = ExecuteMsg::TransferShares {
+            shares_subject: Addr::unchecked("subject"),
+            recipient: Addr::unchecked("subject"),
+            amount: Uint128::new(2),
+        };
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+
+        // moving the whole balance drops the sender as a holder
+        let info = mock_info("friend", &[]);
+        let msg = ExecuteMsg::TransferShares {
+            shares_subject: Addr::unchecked("subject"),
+            recipient: Addr::unchecked("subject"),
+            amount: Uint128::new(1),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        assert_eq!(Uint128::new(2), balance(&deps, "subject"));
+        assert_eq!(Uint128::new(1), holders(&deps));
+
+        // the admin switches transfers off for subjects that opt out
+        let info = mock_info("subject", &[]);
+        let msg_disable = ExecuteMsg::SetTransfersEnabled {
+            shares_subject: Addr::unchecked("subject"),
+            enabled: false,
+        };
+        assert!(execute(deps.as_mut(), mock_env(), info, msg_disable.clone()).is_err());
+        let info = mock_info("creator", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg_disable).unwrap();
+        let info = mock_info("subject", &[]);
+        let msg = ExecuteMsg::TransferShares {
+            shares_subject: Addr::unchecked("subject"),
+            recipient: Addr::unchecked("friend"),
+            amount: Uint128::new(1),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::TransfersDisabled {}));
+    }
+
+    #[test]
+    fn allowances() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        for _ in 0..3 {
+            let info = mock_info("subject", &coins(1000000000000000000, "inj"));
+            let msg = ExecuteMsg::BuyShares {
+                shares_subject: Addr::unchecked("subject"),
+                referral: Addr::unchecked("subject"),
+                recipient: None,
+                deadline: None,
+            };
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+
+        // spending without an allowance fails
+        let info = mock_info("spender", &[]);
+        let msg_transfer = ExecuteMsg::TransferSharesFrom {
+            owner: Addr::unchecked("subject"),
+            shares_subject: Addr::unchecked("subject"),
+            recipient: Addr::unchecked("friend"),
+            amount: Uint128::new(1),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg_transfer.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientAllowance {}));
+
+        // approve two shares
+        let env = mock_env();
+        let info = mock_info("subject", &[]);
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: Addr::unchecked("spender"),
+            shares_subject: Addr::unchecked("subject"),
+            amount: Uint128::new(2),
+            expires: Some(Expiration::AtHeight(env.block.height + 10)),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        // transfer one share on behalf of the owner
+        let info = mock_info("spender", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg_transfer.clone()).unwrap();
+        let msg = QueryMsg::GetShareBalance {
+            shares_subject: Addr::unchecked("subject"),
+            my_address: Addr::unchecked("friend"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        assert_eq!(Uint128::new(1), from_json::<GetShareBalanceResponse>(&res).unwrap().amount);
+
+        // sell one share on behalf of the owner, proceeds go to the owner
+        let info = mock_info("spender", &[]);
+        let msg = ExecuteMsg::SellSharesFrom {
+            owner: Addr::unchecked("subject"),
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            deadline: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        match &res.messages[0].msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, .. }) => assert_eq!("subject", to_address),
+            _ => panic!("expected a bank send"),
+        }
+        let msg = QueryMsg::GetShareBalance {
+            shares_subject: Addr::unchecked("subject"),
+            my_address: Addr::unchecked("subject"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        assert_eq!(Uint128::new(1), from_json::<GetShareBalanceResponse>(&res).unwrap().amount);
+
+        // allowance is used up
+        let msg = QueryMsg::Allowance {
+            owner: Addr::unchecked("subject"),
+            spender: Addr::unchecked("spender"),
+            shares_subject: Addr::unchecked("subject"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        assert_eq!(Uint128::zero(), from_json::<AllowanceResponse>(&res).unwrap().allowance);
+        let info = mock_info("spender", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg_transfer.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientAllowance {}));
+
+        // expired allowances cannot be spent
+        let info = mock_info("subject", &[]);
+        let msg = ExecuteMsg::IncreaseAllowance {
+            spender: Addr::unchecked("spender"),
+            shares_subject: Addr::unchecked("subject"),
+            amount: Uint128::new(1),
+            expires: Some(Expiration::AtHeight(env.block.height + 10)),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let mut env = mock_env();
+        env.block.height += 10;
+        let info = mock_info("spender", &[]);
+        let err = execute(deps.as_mut(), env, info, msg_transfer).unwrap_err();
+        assert!(matches!(err, ContractError::AllowanceExpired {}));
+    }
+
+    #[test]
+    fn operators() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let info = mock_info("subject", &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            recipient: None,
+            deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+
+        let msg_buy = ExecuteMsg::BuySharesFor {
+            owner: Addr::unchecked("user"),
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            deadline: None,
+        };
+        let msg_sell = ExecuteMsg::SellSharesFor {
+            owner: Addr::unchecked("user"),
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            deadline: None,
+        };
+
+        // not approved yet
+        let info = mock_info("bot", &coins(1000000000000000000, "inj"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg_buy.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::OperatorNotApproved {}));
+
+        let info = mock_info("user", &[]);
+        let msg = ExecuteMsg::ApproveOperator {
+            operator: Addr::unchecked("bot"),
+            expires: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let res = query(
+            deps.as_ref(),
+            mock_env(),
+            QueryMsg::Operators {
+                owner: Addr::unchecked("user"),
+            },
+        )
+        .unwrap();
+        let operators = from_json::<OperatorsResponse>(&res).unwrap().operators;
+        assert_eq!(1, operators.len());
+        assert_eq!(Addr::unchecked("bot"), operators[0].operator);
+
+        // the share and the refund go to the owner
+        let info = mock_info("bot", &coins(1000000000000000000, "inj"));
+        let res = execute(deps.as_mut(), mock_env(), info, msg_buy.clone()).unwrap();
+        match &res.messages.last().unwrap().msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, .. }) => assert_eq!("user", to_address),
+            _ => panic!("expected a bank send"),
+        }
+        let msg = QueryMsg::GetShareBalance {
+            shares_subject: Addr::unchecked("subject"),
+            my_address: Addr::unchecked("user"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg.clone()).unwrap();
+        assert_eq!(Uint128::new(1), from_json::<GetShareBalanceResponse>(&res).unwrap().amount);
+
+        // selling pays the proceeds to the owner
+        let info = mock_info("bot", &[]);
+        let res = execute(deps.as_mut(), mock_env(), info, msg_sell.clone()).unwrap();
+        match &res.messages[0].msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, .. }) => assert_eq!("user", to_address),
+            _ => panic!("expected a bank send"),
+        }
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        assert_eq!(Uint128::zero(), from_json::<GetShareBalanceResponse>(&res).unwrap().amount);
+
+        // revoked operators can no longer trade
+        let info = mock_info("user", &[]);
+        let msg = ExecuteMsg::RevokeOperator {
+            operator: Addr::unchecked("bot"),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let info = mock_info("bot", &[]);
+        let err = execute(deps.as_mut(), mock_env(), info, msg_sell).unwrap_err();
+        assert!(matches!(err, ContractError::OperatorNotApproved {}));
+    }
+
+    #[test]
+    fn trade_recipients() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let info = mock_info("subject", &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            recipient: None,
+            deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let balance = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, holder: &str| {
+            let msg = QueryMsg::GetShareBalance {
+                shares_subject: Addr::unchecked("subject"),
+                my_address: Addr::unchecked(holder),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_json::<GetShareBalanceResponse>(&res).unwrap().amount
+        };
+
+        // checkout pays, the friend gets the share, the refund goes back to checkout
+        let info = mock_info("checkout", &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            recipient: Some(Addr::unchecked("friend")),
+            deadline: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        match &res.messages.last().unwrap().msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, .. }) => assert_eq!("checkout", to_address),
+            _ => panic!("expected a bank send"),
+        }
+        assert_eq!(Uint128::zero(), balance(&deps, "checkout"));
+        assert_eq!(Uint128::new(1), balance(&deps, "friend"));
+        let msg = QueryMsg::GetSubjectHolders {
+            shares_subject: Addr::unchecked("subject"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        assert_eq!(Uint128::new(2), from_json::<GetSubjectHoldersResponse>(&res).unwrap().amount);
+
+        // sale proceeds can be paid to another address
+        let info = mock_info("friend", &[]);
+        let msg = ExecuteMsg::SellShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            proceeds_recipient: Some(Addr::unchecked("wallet")),
+            deadline: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        match &res.messages[0].msg {
+            CosmosMsg::Bank(BankMsg::Send { to_address, .. }) => assert_eq!("wallet", to_address),
+            _ => panic!("expected a bank send"),
+        }
+        assert_eq!(Uint128::zero(), balance(&deps, "friend"));
+    }
+
+    #[test]
+    fn batch_trade() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        for subject in ["alice", "bob"] {
+            let info = mock_info(subject, &coins(1000000000000000000, "inj"));
+            let msg = ExecuteMsg::BuyShares {
+                shares_subject: Addr::unchecked(subject),
+                referral: Addr::unchecked(subject),
+                recipient: None,
+                deadline: None,
+            };
+            execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        }
+        let balance = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, subject: &str| {
+            let msg = QueryMsg::GetShareBalance {
+                shares_subject: Addr::unchecked(subject),
+                my_address: Addr::unchecked("trader"),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            from_json::<GetShareBalanceResponse>(&res).unwrap().amount
+        };
+
+        // buy two subjects in one go, one combined payout per recipient
+        let info = mock_info("trader", &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BatchTrade {
+            trades: vec![
+                TradeOp::Buy {
+                    shares_subject: Addr::unchecked("alice"),
+                    referral: Addr::unchecked("ref"),
+                    amount: Uint128::new(2),
+                    max_cost: Uint128::new(1000000000000000000),
+                },
+                TradeOp::Buy {
+                    shares_subject: Addr::unchecked("bob"),
+                    referral: Addr::unchecked("ref"),
+                    amount: Uint128::new(1),
+                    max_cost: Uint128::new(1000000000000000000),
+                },
+            ],
+            deadline: None,
+        };
+        let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(Uint128::new(2), balance(&deps, "alice"));
+        assert_eq!(Uint128::new(1), balance(&deps, "bob"));
+        let recipients: Vec<String> = res
+            .messages
+            .iter()
+            .map(|msg| match &msg.msg {
+                CosmosMsg::Bank(BankMsg::Send { to_address, .. }) => to_address.clone(),
+                _ => panic!("expected a bank send"),
+            })
+            .collect();
+        assert_eq!(vec!["alice", "bob", "creator", "ref", "trader"], recipients);
+
+        // a failing op fails the whole batch
+        let info = mock_info("trader", &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BatchTrade {
+            trades: vec![
+                TradeOp::Sell {
+                    shares_subject: Addr::unchecked("alice"),
+                    referral: Addr::unchecked("ref"),
+                    amount: Uint128::new(1),
+                    min_proceeds: Uint128::zero(),
+                },
+                TradeOp::Sell {
+                    shares_subject: Addr::unchecked("bob"),
+                    referral: Addr::unchecked("ref"),
+                    amount: Uint128::new(2),
+                    min_proceeds: Uint128::zero(),
+                },
+            ],
+            deadline: None,
+        };
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+
+        // slippage bounds are enforced per op
+        let info = mock_info("trader", &[]);
+        let msg = ExecuteMsg::BatchTrade {
+            trades: vec![TradeOp::Sell {
+                shares_subject: Addr::unchecked("alice"),
+                referral: Addr::unchecked("ref"),
+                amount: Uint128::new(1),
+                min_proceeds: Uint128::new(1000000000000000000),
+            }],
+            deadline: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::SlippageExceeded {}));
+
+        // buys without enough funds are rejected
+        let info = mock_info("trader", &[]);
+        let msg = ExecuteMsg::BatchTrade {
+            trades: vec![TradeOp::Buy {
+                shares_subject: Addr::unchecked("bob"),
+                referral: Addr::unchecked("ref"),
+                amount: Uint128::new(1),
+                max_cost: Uint128::new(1000000000000000000),
+            }],
+            deadline: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientPayment {}));
+
+        // batches are capped per op and in total
+        let buy_op = |amount: u128| TradeOp::Buy {
+            shares_subject: Addr::unchecked("bob"),
+            referral: Addr::unchecked("ref"),
+            amount: Uint128::new(amount),
+            max_cost: Uint128::MAX,
+        };
+        for trades in [vec![buy_op(51)], vec![buy_op(50), buy_op(50), buy_op(1)]] {
+            let info = mock_info("trader", &coins(1000000000000000000, "inj"));
+            let msg = ExecuteMsg::BatchTrade {
+                trades,
+                deadline: None,
+            };
+            let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+            assert!(matches!(err, ContractError::BatchTooLarge { .. }));
+        }
+
+        // single buys report underpayment the same way
+        let info = mock_info("trader", &coins(1, "inj"));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("bob"),
+            referral: Addr::unchecked("ref"),
+            recipient: None,
+            deadline: None,
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::InsufficientPayment {}));
+    }
+
+    #[test]
+    fn trade_deadline() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let env = mock_env();
+
+        // a deadline in the future is fine
+        let info = mock_info("subject", &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            recipient: None,
+            deadline: Some(Expiration::AtTime(env.block.time.plus_seconds(60))),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+
+        // the same message landing later is rejected
+        let mut late_env = mock_env();
+        late_env.block.time = late_env.block.time.plus_seconds(60);
+        let info = mock_info("subject", &coins(1000000000000000000, "inj"));
+        let err = execute(deps.as_mut(), late_env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DeadlineExceeded {}));
+
+        // height based deadlines work for sells too
+        let info = mock_info("subject", &[]);
+        let msg = ExecuteMsg::SellShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            proceeds_recipient: None,
+            deadline: Some(Expiration::AtHeight(env.block.height)),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::DeadlineExceeded {}));
+    }
+
+    #[test]
+    fn launch_protection() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let info = mock_info("subject", &[]);
+        let msg = ExecuteMsg::SetLaunchProtection {
+            window: Duration::Height(10),
+            max_shares_per_address: Some(Uint128::new(2)),
+            min_buy_interval: Some(Duration::Height(1)),
+            allowlist_only: true,
+        };
+        execute(deps.as_mut(), mock_env(), info, msg.clone()).unwrap();
+        let info = mock_info("subject", &[]);
+        let msg_allowlist = ExecuteMsg::UpdateLaunchAllowlist {
+            add: vec![Addr::unchecked("fan")],
+            remove: vec![],
+        };
+        execute(deps.as_mut(), mock_env(), info, msg_allowlist).unwrap();
+
+        // the subject's first buy starts the window
+        buy(&mut deps, "subject", "subject", 0).unwrap();
+        let info = mock_info("subject", &[]);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg).is_err());
+
+        // only allowlisted addresses, one buy per block, two shares each
+        let err = buy(&mut deps, "bot", "subject", 0).unwrap_err();
+        assert!(matches!(err, ContractError::LaunchNotAllowlisted {}));
+        buy(&mut deps, "fan", "subject", 0).unwrap();
+        let err = buy(&mut deps, "fan", "subject", 0).unwrap_err();
+        assert!(matches!(err, ContractError::LaunchBuyTooSoon {}));
+        buy(&mut deps, "fan", "subject", 1).unwrap();
+        let err = buy(&mut deps, "fan", "subject", 2).unwrap_err();
+        assert!(matches!(err, ContractError::LaunchCapReached {}));
+
+        // the cap follows the payer and the allowlist covers both sides
+        let buy_for = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                       payer: &str,
+                       recipient: &str| {
+            let mut env = mock_env();
+            env.block.height += 3;
+            let info = mock_info(payer, &coins(1000000000000000000, "inj"));
+            let msg = ExecuteMsg::BuyShares {
+                shares_subject: Addr::unchecked("subject"),
+                referral: Addr::unchecked("subject"),
+                recipient: Some(Addr::unchecked(recipient)),
+                deadline: None,
+            };
+            execute(deps.as_mut(), env, info, msg)
+        };
+        let err = buy_for(&mut deps, "fan", "subject").unwrap_err();
+        assert!(matches!(err, ContractError::LaunchCapReached {}));
+        let err = buy_for(&mut deps, "fan", "bot").unwrap_err();
+        assert!(matches!(err, ContractError::LaunchNotAllowlisted {}));
+        let err = buy_for(&mut deps, "bot", "fan").unwrap_err();
+        assert!(matches!(err, ContractError::LaunchNotAllowlisted {}));
+
+        // after the window anyone can buy
+        buy(&mut deps, "bot", "subject", 10).unwrap();
+        let msg = QueryMsg::LaunchProtection {
+            shares_subject: Addr::unchecked("subject"),
+        };
+        let mut env = mock_env();
+        env.block.height += 10;
+        let res = query(deps.as_ref(), env, msg).unwrap();
+        assert!(!from_json::<LaunchProtectionResponse>(&res).unwrap().is_active);
+    }
+
+    #[test]
+    fn trade_limits() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let info = mock_info("creator", &[]);
+        let msg = ConfigChange::SetTradeLimits {
+            buy_sell_cooldown_blocks: 2,
+            max_trades_per_block: 2,
+        };
+        apply_config_change(&mut deps, info, msg).unwrap();
+        let trade = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                     trader: &str,
+                     is_buy: bool,
+                     blocks: u64| {
+            let mut env = mock_env();
+            env.block.height += blocks;
+            let msg = if is_buy {
+                ExecuteMsg::BuyShares {
+                    shares_subject: Addr::unchecked("subject"),
+                    referral: Addr::unchecked("subject"),
+                    recipient: None,
+                    deadline: None,
+                }
+            } else {
+                ExecuteMsg::SellShares {
+                    shares_subject: Addr::unchecked("subject"),
+                    referral: Addr::unchecked("subject"),
+                    proceeds_recipient: None,
+                    deadline: None,
+                }
+            };
+            let info = mock_info(trader, &coins(1000000000000000000, "inj"));
+            execute(deps.as_mut(), env, info, msg)
+        };
+        trade(&mut deps, "subject", true, 0).unwrap();
+
+        // two trades per block
+        trade(&mut deps, "trader", true, 0).unwrap();
+        trade(&mut deps, "trader", true, 0).unwrap();
+        let err = trade(&mut deps, "trader", true, 0).unwrap_err();
+        assert!(matches!(err, ContractError::TradeRateLimited {}));
+
+        // selling right after buying has to wait for the cooldown
+        let err = trade(&mut deps, "trader", false, 1).unwrap_err();
+        assert!(matches!(err, ContractError::TradeCooldown { blocks_remaining: 1 }));
+        trade(&mut deps, "trader", false, 2).unwrap();
+        let err = trade(&mut deps, "trader", true, 3).unwrap_err();
+        assert!(matches!(err, ContractError::TradeCooldown { blocks_remaining: 1 }));
+        trade(&mut deps, "trader", true, 4).unwrap();
+
+        // the per-block cap follows the payer, not the share recipient
+        let mut env = mock_env();
+        env.block.height += 4;
+        let info = mock_info("trader", &coins(1000000000000000000, "inj"));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            recipient: Some(Addr::unchecked("friend")),
+            deadline: None,
+        };
+        execute(deps.as_mut(), env.clone(), info.clone(), msg.clone()).unwrap();
+        let err = execute(deps.as_mut(), env, info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::TradeRateLimited {}));
+
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::TradeLimits {}).unwrap();
+        let limits: TradeLimits = from_json(&res).unwrap();
+        assert_eq!(2, limits.buy_sell_cooldown_blocks);
+    }
+
+    #[test]
+    fn circuit_breaker() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let info = mock_info("creator", &[]);
+        let msg = ConfigChange::SetCircuitBreaker {
+            max_price_move_percent: Uint128::new(50000), // 50%
+            window_blocks: 10,
+            cooldown_blocks: 5,
+        };
+        apply_config_change(&mut deps, info, msg).unwrap();
+        let buy = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                   amount: u128,
+                   blocks: u64| {
+            let mut env = mock_env();
+            env.block.height += blocks;
+            let info = mock_info("subject", &coins(10000000000000000000, "inj"));
+            let msg = ExecuteMsg::BatchTrade {
+                trades: vec![TradeOp::Buy {
+                    shares_subject: Addr::unchecked("subject"),
+                    referral: Addr::unchecked("subject"),
+                    amount: Uint128::new(amount),
+                    max_cost: Uint128::new(10000000000000000000),
+                }],
+                deadline: None,
+            };
+            execute(deps.as_mut(), env, info, msg)
+        };
+        let tripped =
+            |res: &Response| res.events.iter().any(|event| event.ty == "circuit_breaker_tripped");
+
+        // a new market is exempt while single shares still move the price a lot
+        let res = buy(&mut deps, 10, 0).unwrap();
+        assert!(!tripped(&res));
+
+        // a large batch completes and halts the market afterwards
+        let res = buy(&mut deps, 3, 1).unwrap();
+        assert!(tripped(&res));
+        let err = buy(&mut deps, 1, 2).unwrap_err();
+        assert!(matches!(err, ContractError::CircuitBreakerActive { until_height: _ }));
+        let msg = QueryMsg::CircuitBreaker {
+            shares_subject: Addr::unchecked("subject"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        assert!(from_json::<CircuitBreakerResponse>(&res).unwrap().is_halted);
+
+        // trading resumes after the cooldown
+        buy(&mut deps, 1, 6).unwrap();
+
+        // only the pauser can lift a halt early
+        let msg = ExecuteMsg::ResetCircuitBreaker {
+            shares_subject: Addr::unchecked("subject"),
+        };
+        let info = mock_info("subject", &[]);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg.clone()).is_err());
+        let info = mock_info("creator", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        buy(&mut deps, 1, 7).unwrap();
+    }
+
+    #[test]
+    fn blocklist() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        buy(&mut deps, "subject", "subject", 0).unwrap();
+
+        // only the admin manages the blocklist
+        let msg = ExecuteMsg::UpdateBlocklist {
+            add: vec![Addr::unchecked("sanctioned")],
+            remove: vec![],
+        };
+        let info = mock_info("subject", &[]);
+        assert!(execute(deps.as_mut(), mock_env(), info, msg.clone()).is_err());
+        let info = mock_info("creator", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        let err = buy(&mut deps, "sanctioned", "subject", 0).unwrap_err();
+        assert!(matches!(err, ContractError::AddressBlocked { .. }));
+        let msg = ExecuteMsg::BuyShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("sanctioned"),
+            recipient: None,
+            deadline: None,
+        };
+        let info = mock_info("trader", &coins(1000000000000000000, "inj"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::AddressBlocked { .. }));
+
+        // the screening contract is asked about every address
+        deps.querier.update_wasm(|query| match query {
+            WasmQuery::Smart { contract_addr, msg } if contract_addr == "screening" => {
+                let ScreeningQueryMsg::IsBlocked { address } = from_json(msg).unwrap();
+                let response = IsBlockedResponse {
+                    is_blocked: address == "flagged",
+                };
+                SystemResult::Ok(ContractResult::Ok(to_json_binary(&response).unwrap()))
+            }
+            _ => panic!("unexpected query"),
+        });
+        // only the admin sets the screening contract, through the timelock
+        let msg = ConfigChange::SetScreeningContract {
+            contract: Some(Addr::unchecked("screening")),
+        };
+        let info = mock_info("fee_manager", &[]);
+        let grant = ExecuteMsg::GrantRole {
+            role: Role::FeeManager,
+            account: info.sender.clone(),
+        };
+        execute(deps.as_mut(), mock_env(), mock_info("creator", &[]), grant).unwrap();
+        assert!(apply_config_change(&mut deps, info, msg.clone()).is_err());
+        apply_config_change(&mut deps, mock_info("creator", &[]), msg).unwrap();
+        let err = buy(&mut deps, "flagged", "subject", 0).unwrap_err();
+        assert!(matches!(err, ContractError::AddressBlocked { .. }));
+        buy(&mut deps, "trader", "subject", 0).unwrap();
+        let msg = QueryMsg::IsBlocked {
+            address: Addr::unchecked("flagged"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        assert!(from_json::<IsBlockedResponse>(&res).unwrap().is_blocked);
+
+        // protocol fee recipients are screened too
+        let msg = ConfigChange::SetFeeDestination {
+            fee_destination: Addr::unchecked("flagged"),
+        };
+        let err = apply_config_change(&mut deps, mock_info("creator", &[]), msg).unwrap_err();
+        assert!(matches!(err, ContractError::AddressBlocked { .. }));
+
+        // a screening outage stops buys but never sells
+        deps.querier.update_wasm(|_| {
+            SystemResult::Ok(ContractResult::Err("screening unavailable".to_string()))
+        });
+        let err = buy(&mut deps, "trader", "subject", 0).unwrap_err();
+        assert!(matches!(err, ContractError::ScreeningUnavailable {}));
+        let msg = ExecuteMsg::SellShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            proceeds_recipient: None,
+            deadline: None,
+        };
+        let info = mock_info("trader", &[]);
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+    }
+
+    #[test]
+    fn snapshots() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let start_height = mock_env().block.height;
+        buy(&mut deps, "subject", "subject", 1).unwrap();
+        buy(&mut deps, "buyer", "subject", 2).unwrap();
+        buy(&mut deps, "buyer", "subject", 3).unwrap();
+
+        let mut env = mock_env();
+        env.block.height += 5;
+        let balance_at = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, blocks: u64| {
+            let msg = QueryMsg::BalanceAtHeight {
+                shares_subject: Addr::unchecked("subject"),
+                address: Addr::unchecked("buyer"),
+                height: start_height + blocks,
+            };
+            let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+            let response: BalanceAtHeightResponse = from_json(&res).unwrap();
+            response.balance
+        };
+        // a height reads the value from before the trades in that block
+        assert_eq!(Uint128::zero(), balance_at(&deps, 2));
+        assert_eq!(Uint128::new(1), balance_at(&deps, 3));
+        assert_eq!(Uint128::new(2), balance_at(&deps, 4));
+
+        let msg = QueryMsg::SupplyAtHeight {
+            shares_subject: Addr::unchecked("subject"),
+            height: start_height + 3,
+        };
+        let res = query(deps.as_ref(), env.clone(), msg).unwrap();
+        let response: SupplyAtHeightResponse = from_json(&res).unwrap();
+        assert_eq!(Uint128::new(2), response.supply);
+
+        // no history before instantiation or past the current block
+        let msg = QueryMsg::SupplyAtHeight {
+            shares_subject: Addr::unchecked("subject"),
+            height: start_height - 1,
+        };
+        query(deps.as_ref(), env.clone(), msg).unwrap_err();
+        let msg = QueryMsg::SupplyAtHeight {
+            shares_subject: Addr::unchecked("subject"),
+            height: start_height + 6,
+        };
+        query(deps.as_ref(), env, msg).unwrap_err();
+    }
+
+    #[test]
+    fn holder_rewards() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let info = mock_info("subject", &[]);
+        let msg = ConfigChange::SetHolderRewardPercent {
+            percent: Uint128::new(50000),
+        };
+        apply_config_change(&mut deps, info, msg).unwrap();
+        let buy_reward = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, buyer: &str| {
+            let res = buy(deps, buyer, "subject", 0).unwrap();
+            res.events[0]
+                .attributes
+                .iter()
+                .find(|attr| attr.key == "holder_rewards")
+                .unwrap()
+                .value
+                .parse::<u128>()
+                .unwrap()
+        };
+        let pending = |deps: &OwnedDeps<MockStorage, MockApi, MockQuerier>, holder: &str| {
+            let msg = QueryMsg::PendingHolderRewards {
+                shares_subject: Addr::unchecked("subject"),
+                holder: Addr::unchecked(holder),
+            };
+            let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+            let response: PendingHolderRewardsResponse = from_json(&res).unwrap();
+            response.pending
+        };
+        buy_reward(&mut deps, "subject");
+        // the only holder before the trade gets the whole reward
+        let first_reward = buy_reward(&mut deps, "alice");
+        assert!(first_reward > 0);
+        assert_eq!(Uint128::new(first_reward), pending(&deps, "subject"));
+        assert_eq!(Uint128::zero(), pending(&deps, "alice"));
+
+        // rewards are split over the supply before the trade
+        let second_reward = buy_reward(&mut deps, "bob");
+        assert_eq!(Uint128::new(second_reward / 2), pending(&deps, "alice"));
+
+        // transfers settle before moving shares
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::TransferShares {
+            shares_subject: Addr::unchecked("subject"),
+            recipient: Addr::unchecked("bob"),
+            amount: Uint128::new(1),
+        };
+        execute(deps.as_mut(), mock_env(), info, msg).unwrap();
+        assert_eq!(Uint128::new(second_reward / 2), pending(&deps, "alice"));
+        buy_reward(&mut deps, "carol");
+        assert_eq!(Uint128::new(second_reward / 2), pending(&deps, "alice"));
+
+        // unclaimed rewards are owed on top of the reserve
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::Solvency {}).unwrap();
+        let solvency: SolvencyResponse = from_json(&res).unwrap();
+        assert!(!solvency.total_holder_rewards.is_zero());
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::ClaimHolderRewards {
+            shares_subject: Addr::unchecked("subject"),
+        };
+        let res = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(second_reward / 2, "inj"),
+            }),
+            res.messages[0].msg
+        );
+        assert_eq!(Uint128::zero(), pending(&deps, "alice"));
+        let err = execute(deps.as_mut(), mock_env(), info, msg).unwrap_err();
+        assert!(matches!(err, ContractError::NoHolderRewards {}));
+    }
+
+    #[test]
+    fn airdrops() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let start_height = mock_env().block.height;
+        let at = |blocks: u64| {
+            let mut env = mock_env();
+            env.block.height += blocks;
+            env
+        };
+        buy(&mut deps, "subject", "subject", 1).unwrap();
+        buy(&mut deps, "alice", "subject", 2).unwrap();
+        buy(&mut deps, "alice", "subject", 2).unwrap();
+
+        // only the subject funds its airdrops
+        let msg = ExecuteMsg::FundHolderAirdrop {
+            shares_subject: Addr::unchecked("subject"),
+            expires: Expiration::AtHeight(start_height + 10),
+        };
+        let info = mock_info("alice", &coins(300, "usdc"));
+        let err = execute(deps.as_mut(), at(3), info, msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::Unauthorized {}));
+        let info = mock_info("subject", &coins(300, "usdc"));
+        execute(deps.as_mut(), at(3), info, msg).unwrap();
+
+        // buys in the funding block do not count
+        buy(&mut deps, "bob", "subject", 3).unwrap();
+        let claim = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                     holder: &str,
+                     blocks: u64| {
+            let info = mock_info(holder, &[]);
+            execute(deps.as_mut(), at(blocks), info, ExecuteMsg::ClaimAirdrop { id: 1 })
+        };
+        let err = claim(&mut deps, "bob", 4).unwrap_err();
+        assert!(matches!(err, ContractError::NoAirdropShare {}));
+        let res = claim(&mut deps, "alice", 4).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "alice".to_string(),
+                amount: coins(200, "usdc"),
+            }),
+            res.messages[0].msg
+        );
+        let err = claim(&mut deps, "alice", 4).unwrap_err();
+        assert!(matches!(err, ContractError::NoAirdropShare {}));
+
+        let msg = QueryMsg::AirdropClaim {
+            id: 1,
+            holder: Addr::unchecked("subject"),
+        };
+        let res = query(deps.as_ref(), at(4), msg).unwrap();
+        let response: AirdropClaimResponse = from_json(&res).unwrap();
+        assert_eq!(Uint128::new(100), response.claimable);
+
+        // unclaimed funds go back to the subject after the expiry
+        let info = mock_info("subject", &[]);
+        let msg = ExecuteMsg::ReclaimAirdrop { id: 1 };
+        let err = execute(deps.as_mut(), at(4), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::AirdropNotExpired {}));
+        let err = claim(&mut deps, "subject", 10).unwrap_err();
+        assert!(matches!(err, ContractError::AirdropExpired {}));
+        let res = execute(deps.as_mut(), at(10), info, msg).unwrap();
+        assert_eq!(
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: "subject".to_string(),
+                amount: coins(100, "usdc"),
+            }),
+            res.messages[0].msg
+        );
+
+        // unclaimed airdrops in the quote denom are not surplus
+        let msg = ExecuteMsg::FundHolderAirdrop {
+            shares_subject: Addr::unchecked("subject"),
+            expires: Expiration::AtHeight(start_height + 20),
+        };
+        let info = mock_info("subject", &coins(5000, "inj"));
+        execute(deps.as_mut(), at(11), info, msg).unwrap();
+        let res = query(deps.as_ref(), at(11), QueryMsg::Solvency {}).unwrap();
+        let solvency: SolvencyResponse = from_json(&res).unwrap();
+        deps.querier.update_balance(
+            MOCK_CONTRACT_ADDR,
+            coins(solvency.total_reserve.u128() + 5000, "inj"),
+        );
+        let res = query(deps.as_ref(), at(11), QueryMsg::Solvency {}).unwrap();
+        let solvency: SolvencyResponse = from_json(&res).unwrap();
+        assert_eq!(Uint128::new(5000), solvency.total_airdrops);
+        assert_eq!(Uint128::zero(), solvency.surplus);
+        assert!(solvency.is_solvent);
+    }
+
+    #[test]
+    fn share_locks() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        buy(&mut deps, "subject", "subject", 0).unwrap();
+        for _ in 0..3 {
+            buy(&mut deps, "alice", "subject", 0).unwrap();
+        }
+
+        let info = mock_info("alice", &[]);
+        let msg = ExecuteMsg::LockShares {
+            shares_subject: Addr::unchecked("subject"),
+            amount: Uint128::new(2),
+            duration: 365 * 24 * 60 * 60,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        // only the unlocked share is left to lock, sell or transfer
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::SharesLocked { .. }));
+        let sell = ExecuteMsg::SellShares {
+            shares_subject: Addr::unchecked("subject"),
+            referral: Addr::unchecked("subject"),
+            proceeds_recipient: None,
+            deadline: None,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), sell.clone()).unwrap();
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), sell).unwrap_err();
+        assert!(matches!(err, ContractError::SharesLocked { .. }));
+        let transfer = ExecuteMsg::TransferShares {
+            shares_subject: Addr::unchecked("subject"),
+            recipient: Addr::unchecked("bob"),
+            amount: Uint128::new(1),
+        };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), transfer.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::SharesLocked { .. }));
+
+        let msg = QueryMsg::Locks {
+            owner: Addr::unchecked("alice"),
+        };
+        let res = query(deps.as_ref(), mock_env(), msg).unwrap();
+        let response: LocksResponse = from_json(&res).unwrap();
+        assert_eq!(1, response.locks.len());
+        let lock = response.locks[0].clone();
+        // a full year doubles the weight
+        assert_eq!(Uint128::new(4000), lock.lock.weight);
+
+        // released only once the lock period is over
+        let msg = ExecuteMsg::UnlockShares { id: lock.id };
+        let err = execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap_err();
+        assert!(matches!(err, ContractError::LockNotExpired { .. }));
+        let mut env = mock_env();
+        env.block.time = lock.lock.unlocks_at;
+
+        // only active locks carry weight, an expired lock drops out before it is released
+        let weight_query = QueryMsg::LockedWeight {
+            shares_subject: Addr::unchecked("subject"),
+        };
+        let res = query(deps.as_ref(), mock_env(), weight_query.clone()).unwrap();
+        let response: LockedWeightResponse = from_json(&res).unwrap();
+        assert_eq!(Uint128::new(4000), response.weight);
+        let res = query(deps.as_ref(), env.clone(), weight_query.clone()).unwrap();
+        let response: LockedWeightResponse = from_json(&res).unwrap();
+        assert_eq!(Uint128::zero(), response.weight);
+
+        execute(deps.as_mut(), env, info.clone(), msg).unwrap();
+        execute(deps.as_mut(), mock_env(), info, transfer).unwrap();
+
+        let res = query(deps.as_ref(), mock_env(), weight_query).unwrap();
+        let response: LockedWeightResponse = from_json(&res).unwrap();
+        assert_eq!(Uint128::zero(), response.weight);
+    }
+
+    #[test]
+    fn self_vesting() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let after = |seconds: u64| {
+            let mut env = mock_env();
+            env.block.time = env.block.time.plus_seconds(seconds);
+            env
+        };
+        let sell = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>, seconds: u64| {
+            let msg = ExecuteMsg::SellShares {
+                shares_subject: Addr::unchecked("subject"),
+                referral: Addr::unchecked("subject"),
+                proceeds_recipient: None,
+                deadline: None,
+            };
+            execute(deps.as_mut(), after(seconds), mock_info("subject", &[]), msg)
+        };
+        for _ in 0..3 {
+            buy(&mut deps, "subject", "subject", 0).unwrap();
+        }
+        buy(&mut deps, "alice", "subject", 0).unwrap();
+
+        let info = mock_info("subject", &[]);
+        let msg = ExecuteMsg::SetSelfVesting {
+            amount: Uint128::new(2),
+            cliff: 100,
+            duration: 200,
+        };
+        execute(deps.as_mut(), mock_env(), info.clone(), msg.clone()).unwrap();
+        // a running schedule cannot be replaced
+        let err = execute(deps.as_mut(), after(50), info.clone(), msg).unwrap_err();
+        assert!(matches!(err, ContractError::SharesNotVested { .. }));
+
+        // only the share outside of the schedule can be sold before the cliff
+        sell(&mut deps, 0).unwrap();
+        let err = sell(&mut deps, 99).unwrap_err();
+        assert!(matches!(err, ContractError::SharesNotVested { .. }));
+        let transfer = ExecuteMsg::TransferShares {
+            shares_subject: Addr::unchecked("subject"),
+            recipient: Addr::unchecked("bob"),
+            amount: Uint128::new(1),
+        };
+        let err = execute(deps.as_mut(), after(99), info, transfer).unwrap_err();
+        assert!(matches!(err, ContractError::SharesNotVested { .. }));
+
+        // linear release from the cliff on
+        let msg = QueryMsg::SelfVesting {
+            shares_subject: Addr::unchecked("subject"),
+        };
+        let res = query(deps.as_ref(), after(150), msg).unwrap();
+        let response: SelfVestingResponse = from_json(&res).unwrap();
+        assert_eq!(Uint128::new(1), response.vested);
+        assert_eq!(Uint128::new(1), response.unvested);
+        sell(&mut deps, 150).unwrap();
+        let err = sell(&mut deps, 150).unwrap_err();
+        assert!(matches!(err, ContractError::SharesNotVested { .. }));
+        sell(&mut deps, 200).unwrap();
+    }
+
+    #[test]
+    fn polls() {
+        let mut deps = mock_dependencies();
+
+        // init
+        let info = mock_info("creator", &coins(1000, "inj"));
+        instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        let at = |blocks: u64| {
+            let mut env = mock_env();
+            env.block.height += blocks;
+            env.block.time = env.block.time.plus_seconds(blocks * 5);
+            env
+        };
+        buy(&mut deps, "subject", "subject", 1).unwrap();
+        buy(&mut deps, "alice", "subject", 1).unwrap();
+        buy(&mut deps, "alice", "subject", 1).unwrap();
+        buy(&mut deps, "bob", "subject", 1).unwrap();
+
+        let msg = ExecuteMsg::CreatePoll {
+            question: "Which topic next?".to_string(),
+            options: vec!["rust".to_string(), "go".to_string()],
+            end_time: at(10).block.time,
+        };
+        execute(deps.as_mut(), at(2), mock_info("subject", &[]), msg).unwrap();
+        // shares bought once the poll started do not count
+        buy(&mut deps, "carol", "subject", 2).unwrap();
+        let vote = |deps: &mut OwnedDeps<MockStorage, MockApi, MockQuerier>,
+                    voter: &str,
+                    option: u32,
+                    blocks: u64| {
+            let msg = ExecuteMsg::Vote { poll_id: 1, option };
+            execute(deps.as_mut(), at(blocks), mock_info(voter, &[]), msg)
+        };
+        let err = vote(&mut deps, "carol", 0, 3).unwrap_err();
+        assert!(matches!(err, ContractError::NoVotingPower {}));
+        let err = vote(&mut deps, "alice", 2, 3).unwrap_err();
+        assert!(matches!(err, ContractError::InvalidPollOption {}));
+        vote(&mut deps, "alice", 0, 3).unwrap();
+        let err = vote(&mut deps, "alice", 1, 3).unwrap_err();
+        assert!(matches!(err, ContractError::AlreadyVoted {}));
+        vote(&mut deps, "bob", 1, 4).unwrap();
+        let err = vote(&mut deps, "subject", 1, 10).unwrap_err();
+        assert!(matches!(err, ContractError::PollClosed {}));
+
+        let res = query(deps.as_ref(), at(4), QueryMsg::Poll { id: 1 }).unwrap();
+        let response: PollResponse = from_json(&res).unwrap();
+        assert_eq!(PollStatus::Open, response.status);
+        assert_eq!(vec![Uint128::new(2), Uint128::new(1)], response.poll.tally);
+        let res = query(deps.as_ref(), at(10), QueryMsg::Poll { id: 1 }).unwrap();
+        let response: PollResponse = from_json(&res).unwrap();
+        assert_eq!(PollStatus::Closed, response.status);
+
+        let msg = QueryMsg::PollVote {
+            id: 1,
+            voter: Addr::unchecked("alice"),
+        };
+        let res = query(deps.as_ref(), at(4), msg).unwrap();
+        let response: PollVoteResponse = from_json(&res).unwrap();
+        assert_eq!(Some(0), response.vote.map(|vote| vote.option));
+    }
+
+    #[test]
+    fn set_protocol_fee_percent() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &coins(1000, "inj"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let info = mock_info("creator", &coins(1000, "inj"));
+        let msg = ConfigChange::SetProtocolBuyFeePercent {
+            protocol_buy_fee_percent: Uint128::new(10),
+        };
+
+        // we can just call .unwrap() to assert this was a success
+        let res = apply_config_change(&mut deps, info, msg).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        // it worked, let's query the state
+        let res = query(deps.as_ref(), mock_env(), QueryMsg::GetState {}).unwrap();
+        let state: State = from_json(&res).unwrap();
+        assert_eq!(Uint128::new(10), state.protocol_buy_fee_percent);
+    }
+
+    #[test]
+    fn set_subject_fee_percent() {
+        let mut deps = mock_dependencies();
+
+        let info = mock_info("creator", &coins(1000, "inj"));
+
+        // we can just call .unwrap() to assert this was a success
+        let res = instantiate(deps.as_mut(), mock_env(), info, INSTANTIATE_MSG).unwrap();
+        assert_eq!(0, res.messages.len());
+
+        let info = mock_info("creator", &coins(1000, "inj"));
+        let msg = ConfigChange::SetSubjectBuyFeePercent {
+            subject_buy_fee_percent: Uint128::new(10),
+        };
+
+        // we can just call .unwrap() to assert this was a success
+        let res = apply_config_change(&mut deps, info, msg).unwrap();
         assert_eq!(0, res.messages.len());
 
         // it worked, let's query the state
@@ -128,6 +2258,8 @@ mod tests {
         let msg: ExecuteMsg = ExecuteMsg::BuyShares {
             shares_subject: Addr::unchecked("anyone"),
             referral: Addr::unchecked("anyone"),
+            recipient: None,
+            deadline: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
         println!("{:?}", res.events);
@@ -161,6 +2293,8 @@ mod tests {
         let msg: ExecuteMsg = ExecuteMsg::BuyShares {
             shares_subject: Addr::unchecked("user_1"),
             referral: Addr::unchecked("anyone"),
+            recipient: None,
+            deadline: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -169,6 +2303,8 @@ mod tests {
         let msg: ExecuteMsg = ExecuteMsg::BuyShares {
             shares_subject: Addr::unchecked("user_1"),
             referral: Addr::unchecked("anyone"),
+            recipient: None,
+            deadline: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
         // println!("{:?}", res.events);
@@ -201,6 +2337,8 @@ mod tests {
         let msg = ExecuteMsg::BuyShares {
             shares_subject: Addr::unchecked("anyone"),
             referral: Addr::unchecked("anyone"),
+            recipient: None,
+            deadline: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -209,6 +2347,8 @@ mod tests {
         let msg = ExecuteMsg::BuyShares {
             shares_subject: Addr::unchecked("anyone"),
             referral: Addr::unchecked("anyone"),
+            recipient: None,
+            deadline: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -217,6 +2357,8 @@ mod tests {
         let msg = ExecuteMsg::SellShares {
             shares_subject: Addr::unchecked("anyone"),
             referral: Addr::unchecked("anyone"),
+            proceeds_recipient: None,
+            deadline: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -286,6 +2428,8 @@ mod tests {
         let msg = ExecuteMsg::BuyShares {
             shares_subject: Addr::unchecked("anyone"),
             referral: Addr::unchecked("anyone"),
+            recipient: None,
+            deadline: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg).unwrap();
 
@@ -312,6 +2456,8 @@ mod tests {
         let msg = ExecuteMsg::BuyShares {
             shares_subject: Addr::unchecked("anyone"),
             referral: Addr::unchecked("anyone"),
+            recipient: None,
+            deadline: None,
         };
         let res = execute(deps.as_mut(), mock_env(), info, msg);
         assert!(res.is_err());
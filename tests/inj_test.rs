@@ -55,7 +55,7 @@ mod inj_tests {
 
         assert_eq!(
             contract_state.owner,
-            contract_state.protocol_fee_destination
+            contract_state.protocol_fee_recipients[0].address
         );
 
         // have user 1 buy a share of user 1
@@ -76,6 +76,8 @@ mod inj_tests {
             &ExecuteMsg::BuyShares {
                 shares_subject: Addr::unchecked(user_1.address()),
                 referral: Addr::unchecked(user_2.address()),
+                recipient: None,
+                deadline: None,
             },
             &[Coin::new(100000000000000000, "inj")],
             user_1,
@@ -178,6 +180,8 @@ mod inj_tests {
             &ExecuteMsg::BuyShares {
                 shares_subject: Addr::unchecked(user_1.address()),
                 referral: Addr::unchecked(user_2.address()),
+                recipient: None,
+                deadline: None,
             },
             &[Coin::new(100000000000000000, "inj")],
             user_1,
@@ -206,6 +210,8 @@ mod inj_tests {
             &ExecuteMsg::BuyShares {
                 shares_subject: Addr::unchecked(user_2.address()),
                 referral: Addr::unchecked(user_1.address()),
+                recipient: None,
+                deadline: None,
             },
             &[Coin::new(100000000000000000, "inj")],
 
@@ -235,6 +241,8 @@ mod inj_tests {
             &ExecuteMsg::BuyShares {
                 shares_subject: Addr::unchecked(user_1.address()),
                 referral: Addr::unchecked(user_2.address()),
+                recipient: None,
+                deadline: None,
             },
             funds, // send funds when buying shares
             user_2,
@@ -377,6 +385,8 @@ mod inj_tests {
             &ExecuteMsg::BuyShares {
                 shares_subject: Addr::unchecked(shares_subject),
                 referral: Addr::unchecked(referring_user),
+                recipient: None,
+                deadline: None,
             },
             funds, // empty funds when buying first share
             signer,
@@ -398,6 +408,7 @@ mod inj_tests {
                     shares_subject: Addr::unchecked(&shares_subject),
                     with_fees,
                     is_buy,
+                    referral: None,
                 },
             )
             .unwrap();